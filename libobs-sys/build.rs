@@ -1,6 +1,8 @@
+#[cfg(feature = "bindgen")]
 extern crate bindgen;
-#[cfg(windows)]
+#[cfg(any(windows, feature = "fake"))]
 extern crate cc;
+extern crate pkg_config;
 #[cfg(windows)]
 extern crate regex;
 #[cfg(windows)]
@@ -8,54 +10,226 @@ extern crate winreg;
 
 #[cfg(windows)]
 mod build_win;
+#[cfg(feature = "bindgen")]
+mod generate;
+mod obs_config;
 
 #[cfg(windows)]
 use build_win::find_windows_obs_lib;
 
-use bindgen::callbacks::{MacroParsingBehavior, ParseCallbacks};
+use obs_config::{header_version, read_file};
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+// older headers are missing parts of obs_source_info that the obs wrapper
+// fills in. the wrapper checks the same version when it's loaded.
+const MINIMUM_OBS_VERSION: (u32, u32) = (21, 0);
 
 #[cfg(not(windows))]
 fn find_windows_obs_lib() {}
 
-#[derive(Debug)]
-struct MacroCallback();
+// where the OBS headers were found
+struct ObsHeaders {
+    include_dirs: Vec<PathBuf>,
+    // pkg-config has already told cargo how to link libobs
+    linked: bool,
+}
+
+fn has_headers(dir: &Path) -> bool {
+    dir.join("obs-module.h").is_file()
+}
+
+// tries, in order, LIBOBS_INCLUDE_DIR, pkg-config and the obs submodule. if
+// nothing is found the error says everywhere that was searched.
+fn find_obs_headers() -> Result<ObsHeaders, String> {
+    let mut searched = Vec::new();
 
-impl ParseCallbacks for MacroCallback {
-    fn will_parse_macro(&self, name: &str) -> MacroParsingBehavior {
-        match name {
-            "FP_ZERO" | "FP_SUBNORMAL" | "FP_NORMAL" | "FP_INFINITE" | "FP_NAN" => {
-                MacroParsingBehavior::Ignore
+    if let Some(dir) = env::var_os("LIBOBS_INCLUDE_DIR") {
+        let dir = PathBuf::from(dir);
+        if has_headers(&dir) {
+            return Ok(ObsHeaders {
+                include_dirs: vec![dir],
+                linked: false,
+            });
+        }
+        searched.push(format!(
+            "LIBOBS_INCLUDE_DIR is set to {} but it has no obs-module.h",
+            dir.display()
+        ));
+    }
+
+    // the fake doesn't link against anything, and an explicit LIBOBS_LIB_DIR
+    // wins over whatever pkg-config says
+    let link = !cfg!(feature = "fake") && env::var_os("LIBOBS_LIB_DIR").is_none();
+    match pkg_config::Config::new().cargo_metadata(link).probe("libobs") {
+        Ok(library) => {
+            if let Some(dir) = library.include_paths.iter().find(|dir| has_headers(dir)) {
+                return Ok(ObsHeaders {
+                    include_dirs: vec![dir.clone()],
+                    linked: link,
+                });
             }
-            _ => MacroParsingBehavior::Default,
+            searched.push(format!(
+                "pkg-config found libobs {} but none of {:?} has obs-module.h",
+                library.version, library.include_paths
+            ));
+        }
+        Err(e) => searched.push(format!("pkg-config couldn't find libobs: {}", e)),
+    }
+
+    let submodule = PathBuf::from("obs/libobs");
+    if has_headers(&submodule) {
+        return Ok(ObsHeaders {
+            include_dirs: vec![submodule, PathBuf::from("obs/UI/obs-frontend-api")],
+            linked: false,
+        });
+    }
+    searched.push(format!(
+        "the obs submodule isn't checked out at {}",
+        submodule.display()
+    ));
+
+    Err(searched.join("\n"))
+}
+
+#[cfg(feature = "fake")]
+fn link_obs(_linked: bool) {
+    // the fake defines everything itself except for blog, which is variadic
+    cc::Build::new().file("src/fake/blog.c").compile("fakeblog");
+}
+
+#[cfg(not(feature = "fake"))]
+fn link_obs(linked: bool) {
+    if !linked {
+        // Tell cargo to tell rustc to link the system obs
+        // shared library.
+        println!("cargo:rustc-link-lib=dylib=obs");
+        if let Some(dir) = env::var_os("LIBOBS_LIB_DIR") {
+            println!("cargo:rustc-link-search=native={}", Path::new(&dir).display());
+        } else {
+            find_windows_obs_lib();
         }
     }
+    // there's no pkg-config file for this, but it's next to libobs
+    if cfg!(feature = "frontend") {
+        println!("cargo:rustc-link-lib=dylib=obs-frontend-api");
+    }
+}
+
+// fails the build if the OBS headers are too old, instead of generating
+// bindings the wrapper doesn't match
+fn check_obs_version(headers: &ObsHeaders) -> u32 {
+    let config = read_file(&headers.include_dirs[0].join("obs-config.h"));
+    let major = header_version(&config, "LIBOBS_API_MAJOR_VER");
+    let minor = header_version(&config, "LIBOBS_API_MINOR_VER");
+    let patch = header_version(&config, "LIBOBS_API_PATCH_VER");
+    if (major, minor) < MINIMUM_OBS_VERSION {
+        panic!(
+            "the OBS headers are version {}.{}.{} but at least {}.{} is needed",
+            major, minor, patch, MINIMUM_OBS_VERSION.0, MINIMUM_OBS_VERSION.1
+        );
+    }
+    // available to crates depending on this one as DEP_OBS_VERSION
+    println!("cargo:version={}.{}.{}", major, minor, patch);
+    major
+}
+
+#[cfg(not(feature = "bindgen"))]
+fn pregenerated_path(major: u32) -> PathBuf {
+    let family = env::var("CARGO_CFG_TARGET_FAMILY").unwrap();
+    PathBuf::from(obs_config::bindings_file(major, &family))
+}
+
+// the newest OBS version enabled through a cargo feature
+#[cfg(not(feature = "bindgen"))]
+fn pregenerated_version() -> Option<u32> {
+    if cfg!(feature = "obs-22") {
+        Some(22)
+    } else if cfg!(feature = "obs-21") {
+        Some(21)
+    } else {
+        None
+    }
+}
+
+#[cfg(feature = "bindgen")]
+fn write_bindings(headers: Result<ObsHeaders, String>, out_path: &Path) {
+    let headers = headers.unwrap_or_else(|searched| {
+        panic!(
+            "couldn't find the OBS headers. install the OBS development package, set \
+             LIBOBS_INCLUDE_DIR to the directory containing obs-module.h or run \
+             `git submodule update --init`.\n{}",
+            searched
+        )
+    });
+    check_obs_version(&headers);
+    link_obs(headers.linked);
+    let frontend = cfg!(feature = "frontend");
+    generate::generate(Path::new("wrapper.h"), &headers.include_dirs, frontend)
+        .write_to_file(out_path)
+        .expect("Couldn't write bindings!");
+}
+
+#[cfg(not(feature = "bindgen"))]
+fn write_bindings(headers: Result<ObsHeaders, String>, out_path: &Path) {
+    let major = pregenerated_version().unwrap_or_else(|| {
+        panic!(
+            "libobs-sys needs either the bindgen feature or a feature for the OBS \
+             version to use checked-in bindings for, like obs-22"
+        )
+    });
+    // the headers are optional, but if they're around they had better agree
+    // with the bindings
+    let linked = match headers {
+        Ok(headers) => {
+            let found = check_obs_version(&headers);
+            if found != major {
+                println!(
+                    "cargo:warning=using bindings for OBS {} but the headers are for OBS {}",
+                    major, found
+                );
+            }
+            headers.linked
+        }
+        Err(_) => false,
+    };
+    link_obs(linked);
+
+    let path = pregenerated_path(major);
+    println!("cargo:rerun-if-changed={}", path.display());
+    if !path.is_file() {
+        panic!(
+            "there are no checked-in bindings for OBS {} at {}. generate them with \
+             `cargo run -p libobs-sys-regenerate` or build with the bindgen feature.",
+            major,
+            path.display()
+        );
+    }
+    std::fs::copy(&path, out_path)
+        .unwrap_or_else(|e| panic!("couldn't copy {}: {}", path.display(), e));
 }
 
 fn main() {
-    // Tell cargo to tell rustc to link the system obs
-    // shared library.
-    println!("cargo:rustc-link-lib=dylib=obs");
-
-    find_windows_obs_lib();
-
-    // The bindgen::Builder is the main entry point
-    // to bindgen, and lets you build up options for
-    // the resulting bindings.
-    let bindings = bindgen::Builder::default()
-        .parse_callbacks(Box::new(MacroCallback()))
-        // The input header we would like to generate
-        // bindings for.
-        .header("wrapper.h")
-        // Finish the builder and generate the bindings.
-        .generate()
-        // Unwrap the Result and panic on failure.
-        .expect("Unable to generate bindings");
+    for var in &[
+        "LIBOBS_INCLUDE_DIR",
+        "LIBOBS_LIB_DIR",
+        "LIBOBS_PATH",
+    ] {
+        println!("cargo:rerun-if-env-changed={}", var);
+    }
+    // asking for the variables above turns off rerunning when anything changes
+    for file in &[
+        "build.rs",
+        "build_win.rs",
+        "generate.rs",
+        "obs_config.rs",
+        "wrapper.h",
+        "src/fake/blog.c",
+    ] {
+        println!("cargo:rerun-if-changed={}", file);
+    }
 
     // Write the bindings to the $OUT_DIR/bindings.rs file.
     let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
-    bindings
-        .write_to_file(out_path.join("bindings.rs"))
-        .expect("Couldn't write bindings!");
+    write_bindings(find_obs_headers(), &out_path.join("bindings.rs"));
 }
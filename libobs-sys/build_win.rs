@@ -34,22 +34,32 @@ pub fn find_windows_obs_lib() {
         println!("cargo:rustc-link-search=native={}", path);
         return;
     }
+    generate_lib("obs");
+    if cfg!(feature = "frontend") {
+        generate_lib("obs-frontend-api");
+    }
+}
+
+fn generate_lib(name: &str) {
     // MSVC doesn't link against normal libraries,
     // and Windows doesn't have a standard mechanism for locating build-time dependencies.
     // Try to locate an OBS installation using the registry and then generate a .lib file
-    // containing all symbols exported by libobs.
+    // containing all symbols exported by the DLL.
     let target = env::var("TARGET").unwrap();
+    let dll_name = format!("{}.dll", name);
     if let Some((dll_path, arch)) = RegKey::predef(HKEY_LOCAL_MACHINE)
         .open_subkey_with_flags("SOFTWARE\\OBS Studio", KEY_READ | KEY_WOW64_32KEY)
         .ok()
         .and_then(|key| key.get_value("").ok())
         .and_then(|base_path: String| match target.as_str() {
-            "i686-pc-windows-msvc" => {
-                Some((PathBuf::from(base_path).join("bin\\32bit\\obs.dll"), "X86"))
-            }
-            "x86_64-pc-windows-msvc" => {
-                Some((PathBuf::from(base_path).join("bin\\64bit\\obs.dll"), "X64"))
-            }
+            "i686-pc-windows-msvc" => Some((
+                PathBuf::from(base_path).join("bin\\32bit").join(&dll_name),
+                "X86",
+            )),
+            "x86_64-pc-windows-msvc" => Some((
+                PathBuf::from(base_path).join("bin\\64bit").join(&dll_name),
+                "X64",
+            )),
             _ => None,
         }) {
         let dumpbin = cc::windows_registry::find(&target, "dumpbin.exe");
@@ -57,8 +67,8 @@ pub fn find_windows_obs_lib() {
         match (dumpbin, lib) {
             (Some(mut dumpbin), Some(mut lib)) => {
                 let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
-                let def_path = out_path.join("obs.def");
-                let lib_path = out_path.join("obs.lib");
+                let def_path = out_path.join(format!("{}.def", name));
+                let lib_path = out_path.join(format!("{}.lib", name));
                 if let Ok(()) = generate_def(dumpbin, &dll_path, &def_path) {
                     assert!(
                         lib.arg(format!("/DEF:{}", def_path.to_str().unwrap()))
@@ -76,6 +86,5 @@ pub fn find_windows_obs_lib() {
             }
             _ => {}
         }
-        return;
     }
 }
@@ -0,0 +1,45 @@
+// bindgen setup shared by build.rs, for the bindgen feature, and by the
+// regenerate tool that writes the checked-in bindings, so both produce the
+// same output from the same headers.
+
+use bindgen::{self, Bindings};
+use bindgen::callbacks::{MacroParsingBehavior, ParseCallbacks};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug)]
+struct MacroCallback();
+
+impl ParseCallbacks for MacroCallback {
+    fn will_parse_macro(&self, name: &str) -> MacroParsingBehavior {
+        match name {
+            "FP_ZERO" | "FP_SUBNORMAL" | "FP_NORMAL" | "FP_INFINITE" | "FP_NAN" => {
+                MacroParsingBehavior::Ignore
+            }
+            _ => MacroParsingBehavior::Default,
+        }
+    }
+}
+
+// `wrapper` is libobs-sys's wrapper.h. `frontend` adds obs-frontend-api, whose
+// header has to be in one of `include_dirs`.
+pub fn generate(wrapper: &Path, include_dirs: &[PathBuf], frontend: bool) -> Bindings {
+    // The bindgen::Builder is the main entry point
+    // to bindgen, and lets you build up options for
+    // the resulting bindings.
+    let mut builder = bindgen::Builder::default()
+        .parse_callbacks(Box::new(MacroCallback()))
+        // The input header we would like to generate
+        // bindings for.
+        .header(wrapper.to_string_lossy().into_owned());
+    for dir in include_dirs {
+        builder = builder.clang_arg(format!("-I{}", dir.display()));
+    }
+    if frontend {
+        builder = builder.clang_arg("-DLIBOBS_SYS_FRONTEND");
+    }
+    builder
+        // Finish the builder and generate the bindings.
+        .generate()
+        // Unwrap the Result and panic on failure.
+        .expect("Unable to generate bindings")
+}
@@ -0,0 +1,36 @@
+// reading obs-config.h, shared by build.rs and the regenerate tool
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+pub fn read_file(path: &Path) -> String {
+    let mut contents = String::new();
+    File::open(path)
+        .and_then(|mut file| file.read_to_string(&mut contents))
+        .unwrap_or_else(|e| panic!("couldn't read {}: {}", path.display(), e));
+    contents
+}
+
+pub fn header_version(config: &str, name: &str) -> u32 {
+    config
+        .lines()
+        .filter_map(|line| {
+            let mut words = line.split_whitespace();
+            match (words.next(), words.next(), words.next()) {
+                (Some("#define"), Some(define), Some(value)) if define == name => {
+                    value.parse().ok()
+                }
+                _ => None,
+            }
+        })
+        .next()
+        .unwrap_or_else(|| panic!("obs-config.h doesn't define {}", name))
+}
+
+// the checked-in bindings for an OBS major version, relative to libobs-sys.
+// they differ between windows and everything else because of things like the
+// size of long, so `family` is "windows" or "unix".
+pub fn bindings_file(major: u32, family: &str) -> String {
+    format!("src/bindings/obs-{}-{}.rs", major, family)
+}
@@ -0,0 +1,47 @@
+// generating libobs-sys's checked-in bindings, shared by the regenerate tool
+// and the test that checks them
+
+extern crate bindgen;
+
+#[path = "../../generate.rs"]
+mod generate;
+#[path = "../../obs_config.rs"]
+mod obs_config;
+
+use obs_config::{bindings_file, header_version};
+use std::path::{Path, PathBuf};
+
+pub use obs_config::read_file;
+
+// the libobs-sys directory
+pub fn sys_dir() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap()
+}
+
+// the obs submodule, used when no other checkout is given
+pub fn default_obs_dir() -> PathBuf {
+    sys_dir().join("obs")
+}
+
+// whether `obs` looks like an OBS source checkout, which the submodule isn't
+// until it's been checked out
+pub fn has_headers(obs: &Path) -> bool {
+    obs.join("libobs").join("obs-config.h").is_file()
+}
+
+// the major version of the OBS checkout at `obs`, where its bindings belong
+// and what bindgen produces for them
+pub fn generate(obs: &Path) -> (u32, PathBuf, String) {
+    let libobs = obs.join("libobs");
+    let config = read_file(&libobs.join("obs-config.h"));
+    let major = header_version(&config, "LIBOBS_API_MAJOR_VER");
+    let family = if cfg!(windows) { "windows" } else { "unix" };
+    let path = sys_dir().join(bindings_file(major, family));
+
+    // always with obs-frontend-api, so the same file works with and without
+    // the frontend feature
+    let include_dirs = [libobs, obs.join("UI").join("obs-frontend-api")];
+    let bindings =
+        generate::generate(&sys_dir().join("wrapper.h"), &include_dirs, true).to_string();
+    (major, path, bindings)
+}
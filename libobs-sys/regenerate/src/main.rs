@@ -0,0 +1,54 @@
+// writes libobs-sys's checked-in bindings from an OBS source checkout, which
+// is the obs submodule unless another one is given:
+//
+//     cargo run -p libobs-sys-regenerate -- [--check] [path/to/obs-studio]
+//
+// with --check nothing is written and it fails if the checked-in bindings
+// are missing or differ from what bindgen produces. `cargo test -p
+// libobs-sys-regenerate` does the same check for the submodule.
+// the bindings depend on the platform, so the windows ones have to be
+// written on windows.
+
+extern crate libobs_sys_regenerate;
+
+use libobs_sys_regenerate::{default_obs_dir, generate, read_file};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+fn main() {
+    let mut check = false;
+    let mut obs = None;
+    for arg in env::args().skip(1) {
+        if arg == "--check" {
+            check = true;
+        } else {
+            obs = Some(PathBuf::from(arg));
+        }
+    }
+    let obs = obs.unwrap_or_else(default_obs_dir);
+    let (major, path, bindings) = generate(&obs);
+
+    if check {
+        if !path.is_file() {
+            eprintln!("there are no checked-in bindings for OBS {} at {}", major, path.display());
+            process::exit(1);
+        }
+        if read_file(&path) != bindings {
+            eprintln!(
+                "{} doesn't match the bindings generated from {}. run \
+                 `cargo run -p libobs-sys-regenerate` to update it.",
+                path.display(),
+                obs.display()
+            );
+            process::exit(1);
+        }
+        println!("{} is up to date", path.display());
+    } else {
+        fs::create_dir_all(path.parent().unwrap())
+            .and_then(|_| fs::write(&path, bindings))
+            .unwrap_or_else(|e| panic!("couldn't write {}: {}", path.display(), e));
+        println!("wrote {}", path.display());
+    }
+}
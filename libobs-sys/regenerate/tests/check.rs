@@ -0,0 +1,32 @@
+// the checked-in bindings have to match what bindgen makes from the obs
+// submodule, or the features that use them would build against stale
+// declarations
+
+extern crate libobs_sys_regenerate;
+
+use libobs_sys_regenerate::{default_obs_dir, generate, has_headers, read_file};
+
+#[test]
+fn checked_in_bindings_match_bindgen() {
+    let obs = default_obs_dir();
+    assert!(
+        has_headers(&obs),
+        "{} isn't checked out. run `git submodule update --init`.",
+        obs.display()
+    );
+    let (major, path, bindings) = generate(&obs);
+    assert!(
+        path.is_file(),
+        "there are no checked-in bindings for OBS {} at {}. run \
+         `cargo run -p libobs-sys-regenerate` to write them.",
+        major,
+        path.display()
+    );
+    assert!(
+        read_file(&path) == bindings,
+        "{} doesn't match the bindings generated from {}. run \
+         `cargo run -p libobs-sys-regenerate` to update it.",
+        path.display(),
+        obs.display()
+    );
+}
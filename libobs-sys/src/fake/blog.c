@@ -0,0 +1,18 @@
+#include <stdarg.h>
+#include <stdio.h>
+
+// blog is variadic, which Rust can't define, so this formats the message and
+// passes it on to the Rust side of the fake.
+void fake_blog_message(int log_level, const char *message);
+
+void blog(int log_level, const char *format, ...)
+{
+	char message[4096];
+	va_list args;
+
+	va_start(args, format);
+	vsnprintf(message, sizeof(message), format, args);
+	va_end(args);
+
+	fake_blog_message(log_level, message);
+}
@@ -0,0 +1,859 @@
+use serde_json::{self, Map, Number};
+use std::ffi::{CStr, CString};
+use std::fs;
+use std::os::raw::c_char;
+use std::path::Path;
+use std::ptr;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use super::super::*;
+
+const EMPTY: &[u8] = b"\0";
+
+// which of an item's values is being used
+const USER: usize = 0;
+const DEFAULT: usize = 1;
+const AUTOSELECT: usize = 2;
+
+enum Value {
+    String(CString),
+    Int(i64),
+    Double(f64),
+    Bool(bool),
+    Object(*mut Data),
+    Array(*mut DataArray),
+}
+
+impl Clone for Value {
+    fn clone(&self) -> Self {
+        unsafe {
+            match *self {
+                Value::String(ref s) => Value::String(s.clone()),
+                Value::Int(i) => Value::Int(i),
+                Value::Double(d) => Value::Double(d),
+                Value::Bool(b) => Value::Bool(b),
+                Value::Object(obj) => {
+                    obs_data_addref(obj as *mut obs_data_t);
+                    Value::Object(obj)
+                }
+                Value::Array(array) => {
+                    obs_data_array_addref(array as *mut obs_data_array_t);
+                    Value::Array(array)
+                }
+            }
+        }
+    }
+}
+
+impl Drop for Value {
+    fn drop(&mut self) {
+        unsafe {
+            match *self {
+                Value::Object(obj) => obs_data_release(obj as *mut obs_data_t),
+                Value::Array(array) => obs_data_array_release(array as *mut obs_data_array_t),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Value {
+    fn data_type(&self) -> obs_data_type {
+        match *self {
+            Value::String(_) => obs_data_type_OBS_DATA_STRING,
+            Value::Int(_) | Value::Double(_) => obs_data_type_OBS_DATA_NUMBER,
+            Value::Bool(_) => obs_data_type_OBS_DATA_BOOLEAN,
+            Value::Object(_) => obs_data_type_OBS_DATA_OBJECT,
+            Value::Array(_) => obs_data_type_OBS_DATA_ARRAY,
+        }
+    }
+
+    unsafe fn to_json(&self) -> serde_json::Value {
+        match *self {
+            Value::String(ref s) => serde_json::Value::String(s.to_string_lossy().into_owned()),
+            Value::Int(i) => serde_json::Value::Number(i.into()),
+            Value::Double(d) => Number::from_f64(d)
+                .map(serde_json::Value::Number)
+                .unwrap_or(serde_json::Value::Null),
+            Value::Bool(b) => serde_json::Value::Bool(b),
+            Value::Object(obj) => (*obj).to_json(),
+            Value::Array(array) => serde_json::Value::Array(
+                (*array)
+                    .items
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .map(|&obj| (*obj).to_json())
+                    .collect(),
+            ),
+        }
+    }
+
+    unsafe fn from_json(json: &serde_json::Value) -> Option<Self> {
+        Some(match *json {
+            serde_json::Value::String(ref s) => Value::String(cstring(s)),
+            serde_json::Value::Number(ref n) => match n.as_i64() {
+                Some(i) => Value::Int(i),
+                None => Value::Double(n.as_f64().unwrap_or(0.0)),
+            },
+            serde_json::Value::Bool(b) => Value::Bool(b),
+            serde_json::Value::Object(ref map) => Value::Object(Data::from_json(map)),
+            serde_json::Value::Array(ref items) => {
+                let array = Box::into_raw(Box::new(DataArray::new()));
+                for item in items {
+                    if let serde_json::Value::Object(ref map) = *item {
+                        (*array).items.lock().unwrap().push(Data::from_json(map));
+                    }
+                }
+                Value::Array(array)
+            }
+            serde_json::Value::Null => return None,
+        })
+    }
+}
+
+fn cstring(s: &str) -> CString {
+    CString::new(s.replace('\0', "")).unwrap()
+}
+
+struct Item {
+    name: CString,
+    values: [Option<Value>; 3],
+}
+
+impl Item {
+    // the user value if there is one, otherwise the default
+    fn value(&self) -> Option<&Value> {
+        self.values[USER].as_ref().or(self.values[DEFAULT].as_ref())
+    }
+}
+
+pub struct Data {
+    refs: AtomicUsize,
+    items: Mutex<Vec<Item>>,
+    // keeps the string from obs_data_get_json alive
+    json: Mutex<CString>,
+}
+
+impl Data {
+    fn new() -> Self {
+        Data {
+            refs: AtomicUsize::new(1),
+            items: Mutex::new(Vec::new()),
+            json: Mutex::new(CString::default()),
+        }
+    }
+
+    unsafe fn from_json(map: &Map<String, serde_json::Value>) -> *mut Data {
+        let data = Data::new();
+        {
+            let mut items = data.items.lock().unwrap();
+            for (name, value) in map {
+                if let Some(value) = Value::from_json(value) {
+                    items.push(Item {
+                        name: cstring(name),
+                        values: [Some(value), None, None],
+                    });
+                }
+            }
+        }
+        Box::into_raw(Box::new(data))
+    }
+
+    unsafe fn to_json(&self) -> serde_json::Value {
+        let mut map = Map::new();
+        for item in self.items.lock().unwrap().iter() {
+            if let Some(ref value) = item.values[USER] {
+                map.insert(item.name.to_string_lossy().into_owned(), value.to_json());
+            }
+        }
+        serde_json::Value::Object(map)
+    }
+
+    fn set(&self, name: &CStr, which: usize, value: Option<Value>) {
+        let mut items = self.items.lock().unwrap();
+        match items.iter().position(|item| item.name.as_c_str() == name) {
+            Some(index) => {
+                items[index].values[which] = value;
+                if items[index].values.iter().all(|v| v.is_none()) {
+                    items.remove(index);
+                }
+            }
+            None => if value.is_some() {
+                let mut values = [None, None, None];
+                values[which] = value;
+                items.push(Item {
+                    name: name.to_owned(),
+                    values,
+                });
+            },
+        }
+    }
+
+    // `which` is None for the user value falling back to the default
+    fn with_value<F, R>(&self, name: &CStr, which: Option<usize>, f: F) -> Option<R>
+    where
+        F: FnOnce(&Value) -> Option<R>,
+    {
+        let items = self.items.lock().unwrap();
+        items
+            .iter()
+            .find(|item| item.name.as_c_str() == name)
+            .and_then(|item| match which {
+                Some(which) => item.values[which].as_ref(),
+                None => item.value(),
+            })
+            .and_then(f)
+    }
+}
+
+pub struct DataArray {
+    refs: AtomicUsize,
+    items: Mutex<Vec<*mut Data>>,
+}
+
+impl DataArray {
+    fn new() -> Self {
+        DataArray {
+            refs: AtomicUsize::new(1),
+            items: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+unsafe fn data<'a>(data: *mut obs_data_t) -> &'a Data {
+    &*(data as *mut Data)
+}
+
+unsafe fn name<'a>(name: *const c_char) -> &'a CStr {
+    CStr::from_ptr(name)
+}
+
+unsafe fn string_value(value: *const c_char) -> Value {
+    if value.is_null() {
+        Value::String(CString::default())
+    } else {
+        Value::String(CStr::from_ptr(value).to_owned())
+    }
+}
+
+unsafe fn object_value(obj: *mut obs_data_t) -> Option<Value> {
+    if obj.is_null() {
+        None
+    } else {
+        obs_data_addref(obj);
+        Some(Value::Object(obj as *mut Data))
+    }
+}
+
+unsafe fn array_value(array: *mut obs_data_array_t) -> Option<Value> {
+    if array.is_null() {
+        None
+    } else {
+        obs_data_array_addref(array);
+        Some(Value::Array(array as *mut DataArray))
+    }
+}
+
+// the string is only valid until the item changes, like in OBS
+fn get_string(value: &Value) -> Option<*const c_char> {
+    match *value {
+        Value::String(ref s) => Some(s.as_ptr()),
+        _ => None,
+    }
+}
+
+fn get_int(value: &Value) -> Option<i64> {
+    match *value {
+        Value::Int(i) => Some(i),
+        Value::Double(d) => Some(d as i64),
+        _ => None,
+    }
+}
+
+fn get_double(value: &Value) -> Option<f64> {
+    match *value {
+        Value::Int(i) => Some(i as f64),
+        Value::Double(d) => Some(d),
+        _ => None,
+    }
+}
+
+fn get_bool(value: &Value) -> Option<bool> {
+    match *value {
+        Value::Bool(b) => Some(b),
+        _ => None,
+    }
+}
+
+// returns a new reference
+unsafe fn get_obj(value: &Value) -> Option<*mut obs_data_t> {
+    match *value {
+        Value::Object(obj) => {
+            obs_data_addref(obj as *mut obs_data_t);
+            Some(obj as *mut obs_data_t)
+        }
+        _ => None,
+    }
+}
+
+unsafe fn get_array(value: &Value) -> Option<*mut obs_data_array_t> {
+    match *value {
+        Value::Array(array) => {
+            obs_data_array_addref(array as *mut obs_data_array_t);
+            Some(array as *mut obs_data_array_t)
+        }
+        _ => None,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_data_create() -> *mut obs_data_t {
+    Box::into_raw(Box::new(Data::new())) as *mut obs_data_t
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_data_create_from_json(json_string: *const c_char) -> *mut obs_data_t {
+    let json = CStr::from_ptr(json_string).to_string_lossy();
+    match serde_json::from_str::<serde_json::Value>(&json) {
+        Ok(serde_json::Value::Object(ref map)) => Data::from_json(map) as *mut obs_data_t,
+        // OBS logs the error and carries on with nothing
+        _ => obs_data_create(),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_data_create_from_json_file(
+    json_file: *const c_char,
+) -> *mut obs_data_t {
+    let path = CStr::from_ptr(json_file).to_string_lossy().into_owned();
+    match fs::read_to_string(path) {
+        Ok(json) => obs_data_create_from_json(cstring(&json).as_ptr()),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_data_create_from_json_file_safe(
+    json_file: *const c_char,
+    backup_ext: *const c_char,
+) -> *mut obs_data_t {
+    let data = obs_data_create_from_json_file(json_file);
+    if !data.is_null() || backup_ext.is_null() {
+        return data;
+    }
+    let backup = format!(
+        "{}.{}",
+        CStr::from_ptr(json_file).to_string_lossy(),
+        CStr::from_ptr(backup_ext)
+            .to_string_lossy()
+            .trim_left_matches('.')
+    );
+    obs_data_create_from_json_file(cstring(&backup).as_ptr())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_data_addref(data: *mut obs_data_t) {
+    if !data.is_null() {
+        (*(data as *mut Data)).refs.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_data_release(data: *mut obs_data_t) {
+    if !data.is_null() && (*(data as *mut Data)).refs.fetch_sub(1, Ordering::SeqCst) == 1 {
+        drop(Box::from_raw(data as *mut Data));
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_data_get_json(data_: *mut obs_data_t) -> *const c_char {
+    let data = data(data_);
+    let json = cstring(&data.to_json().to_string());
+    let mut cache = data.json.lock().unwrap();
+    *cache = json;
+    cache.as_ptr()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_data_save_json(data: *mut obs_data_t, file: *const c_char) -> bool {
+    let json = CStr::from_ptr(obs_data_get_json(data)).to_bytes().to_vec();
+    fs::write(CStr::from_ptr(file).to_string_lossy().into_owned(), json).is_ok()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_data_save_json_safe(
+    data: *mut obs_data_t,
+    file: *const c_char,
+    temp_ext: *const c_char,
+    backup_ext: *const c_char,
+) -> bool {
+    let file = CStr::from_ptr(file).to_string_lossy().into_owned();
+    let extension = |ext: *const c_char| {
+        format!(
+            "{}.{}",
+            file,
+            CStr::from_ptr(ext).to_string_lossy().trim_left_matches('.')
+        )
+    };
+    let temp = extension(temp_ext);
+    if !obs_data_save_json(data, cstring(&temp).as_ptr()) {
+        return false;
+    }
+    if !backup_ext.is_null() && Path::new(&file).exists() {
+        let _ = fs::rename(&file, extension(backup_ext));
+    }
+    fs::rename(&temp, &file).is_ok()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_data_apply(target: *mut obs_data_t, apply_data: *mut obs_data_t) {
+    if target.is_null() || apply_data.is_null() || target == apply_data {
+        return;
+    }
+    let values: Vec<(CString, Value)> = data(apply_data)
+        .items
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|item| {
+            item.values[USER]
+                .as_ref()
+                .map(|value| (item.name.clone(), value.clone()))
+        })
+        .collect();
+    let target = data(target);
+    for (name, value) in values {
+        target.set(&name, USER, Some(value));
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_data_erase(data_: *mut obs_data_t, name_: *const c_char) {
+    let name = name(name_);
+    data(data_)
+        .items
+        .lock()
+        .unwrap()
+        .retain(|item| item.name.as_c_str() != name);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_data_clear(data_: *mut obs_data_t) {
+    let mut items = data(data_).items.lock().unwrap();
+    for item in items.iter_mut() {
+        item.values[USER] = None;
+    }
+    items.retain(|item| item.values.iter().any(|v| v.is_some()));
+}
+
+macro_rules! fake_data_values {
+    ($which:expr, $get_which:expr, $set_string:ident, $set_int:ident, $set_double:ident,
+     $set_bool:ident, $set_obj:ident, $get_string:ident, $get_int:ident, $get_double:ident,
+     $get_bool:ident, $get_obj:ident, $has_value:ident, $unset_value:ident) => {
+        #[no_mangle]
+        pub unsafe extern "C" fn $set_string(
+            data_: *mut obs_data_t,
+            name_: *const c_char,
+            val: *const c_char,
+        ) {
+            data(data_).set(name(name_), $which, Some(string_value(val)));
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn $set_int(data_: *mut obs_data_t, name_: *const c_char, val: i64) {
+            data(data_).set(name(name_), $which, Some(Value::Int(val)));
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn $set_double(
+            data_: *mut obs_data_t,
+            name_: *const c_char,
+            val: f64,
+        ) {
+            data(data_).set(name(name_), $which, Some(Value::Double(val)));
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn $set_bool(
+            data_: *mut obs_data_t,
+            name_: *const c_char,
+            val: bool,
+        ) {
+            data(data_).set(name(name_), $which, Some(Value::Bool(val)));
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn $set_obj(
+            data_: *mut obs_data_t,
+            name_: *const c_char,
+            obj: *mut obs_data_t,
+        ) {
+            data(data_).set(name(name_), $which, object_value(obj));
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn $get_string(
+            data_: *mut obs_data_t,
+            name_: *const c_char,
+        ) -> *const c_char {
+            data(data_)
+                .with_value(name(name_), $get_which, get_string)
+                .unwrap_or(EMPTY.as_ptr() as *const c_char)
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn $get_int(data_: *mut obs_data_t, name_: *const c_char) -> i64 {
+            data(data_)
+                .with_value(name(name_), $get_which, get_int)
+                .unwrap_or(0)
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn $get_double(data_: *mut obs_data_t, name_: *const c_char) -> f64 {
+            data(data_)
+                .with_value(name(name_), $get_which, get_double)
+                .unwrap_or(0.0)
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn $get_bool(data_: *mut obs_data_t, name_: *const c_char) -> bool {
+            data(data_)
+                .with_value(name(name_), $get_which, get_bool)
+                .unwrap_or(false)
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn $get_obj(
+            data_: *mut obs_data_t,
+            name_: *const c_char,
+        ) -> *mut obs_data_t {
+            data(data_)
+                .with_value(name(name_), $get_which, |value| get_obj(value))
+                .unwrap_or(ptr::null_mut())
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn $has_value(data_: *mut obs_data_t, name_: *const c_char) -> bool {
+            data(data_)
+                .with_value(name(name_), Some($which), |_| Some(()))
+                .is_some()
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn $unset_value(data_: *mut obs_data_t, name_: *const c_char) {
+            data(data_).set(name(name_), $which, None);
+        }
+    };
+}
+
+// the plain getters fall back to the default value
+fake_data_values!(
+    USER,
+    None,
+    obs_data_set_string,
+    obs_data_set_int,
+    obs_data_set_double,
+    obs_data_set_bool,
+    obs_data_set_obj,
+    obs_data_get_string,
+    obs_data_get_int,
+    obs_data_get_double,
+    obs_data_get_bool,
+    obs_data_get_obj,
+    obs_data_has_user_value,
+    obs_data_unset_user_value
+);
+
+fake_data_values!(
+    DEFAULT,
+    Some(DEFAULT),
+    obs_data_set_default_string,
+    obs_data_set_default_int,
+    obs_data_set_default_double,
+    obs_data_set_default_bool,
+    obs_data_set_default_obj,
+    obs_data_get_default_string,
+    obs_data_get_default_int,
+    obs_data_get_default_double,
+    obs_data_get_default_bool,
+    obs_data_get_default_obj,
+    obs_data_has_default_value,
+    obs_data_unset_default_value
+);
+
+fake_data_values!(
+    AUTOSELECT,
+    Some(AUTOSELECT),
+    obs_data_set_autoselect_string,
+    obs_data_set_autoselect_int,
+    obs_data_set_autoselect_double,
+    obs_data_set_autoselect_bool,
+    obs_data_set_autoselect_obj,
+    obs_data_get_autoselect_string,
+    obs_data_get_autoselect_int,
+    obs_data_get_autoselect_double,
+    obs_data_get_autoselect_bool,
+    obs_data_get_autoselect_obj,
+    obs_data_has_autoselect_value,
+    obs_data_unset_autoselect_value
+);
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_data_set_array(
+    data_: *mut obs_data_t,
+    name_: *const c_char,
+    array: *mut obs_data_array_t,
+) {
+    data(data_).set(name(name_), USER, array_value(array));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_data_get_array(
+    data_: *mut obs_data_t,
+    name_: *const c_char,
+) -> *mut obs_data_array_t {
+    data(data_)
+        .with_value(name(name_), None, |value| get_array(value))
+        .unwrap_or(ptr::null_mut())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_data_array_create() -> *mut obs_data_array_t {
+    Box::into_raw(Box::new(DataArray::new())) as *mut obs_data_array_t
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_data_array_addref(array: *mut obs_data_array_t) {
+    if !array.is_null() {
+        (*(array as *mut DataArray))
+            .refs
+            .fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_data_array_release(array: *mut obs_data_array_t) {
+    if array.is_null() {
+        return;
+    }
+    let array = array as *mut DataArray;
+    if (*array).refs.fetch_sub(1, Ordering::SeqCst) == 1 {
+        let array = Box::from_raw(array);
+        for &obj in array.items.lock().unwrap().iter() {
+            obs_data_release(obj as *mut obs_data_t);
+        }
+    }
+}
+
+unsafe fn array<'a>(array: *mut obs_data_array_t) -> &'a DataArray {
+    &*(array as *mut DataArray)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_data_array_count(array_: *mut obs_data_array_t) -> usize {
+    array(array_).items.lock().unwrap().len()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_data_array_item(
+    array_: *mut obs_data_array_t,
+    idx: usize,
+) -> *mut obs_data_t {
+    match array(array_).items.lock().unwrap().get(idx) {
+        Some(&obj) => {
+            obs_data_addref(obj as *mut obs_data_t);
+            obj as *mut obs_data_t
+        }
+        None => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_data_array_push_back(
+    array_: *mut obs_data_array_t,
+    obj: *mut obs_data_t,
+) -> usize {
+    if obj.is_null() {
+        return 0;
+    }
+    obs_data_addref(obj);
+    let mut items = array(array_).items.lock().unwrap();
+    items.push(obj as *mut Data);
+    items.len() - 1
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_data_array_insert(
+    array_: *mut obs_data_array_t,
+    idx: usize,
+    obj: *mut obs_data_t,
+) {
+    if obj.is_null() {
+        return;
+    }
+    obs_data_addref(obj);
+    let mut items = array(array_).items.lock().unwrap();
+    let idx = idx.min(items.len());
+    items.insert(idx, obj as *mut Data);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_data_array_erase(array_: *mut obs_data_array_t, idx: usize) {
+    let mut items = array(array_).items.lock().unwrap();
+    if idx < items.len() {
+        obs_data_release(items.remove(idx) as *mut obs_data_t);
+    }
+}
+
+// items remember their position, so they don't notice the data changing
+struct ItemCursor {
+    data: *mut obs_data_t,
+    index: usize,
+}
+
+unsafe fn with_item<F, R>(item: *mut obs_data_item_t, f: F) -> Option<R>
+where
+    F: FnOnce(&Item) -> Option<R>,
+{
+    let cursor = &*(item as *mut ItemCursor);
+    data(cursor.data)
+        .items
+        .lock()
+        .unwrap()
+        .get(cursor.index)
+        .and_then(f)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_data_first(data_: *mut obs_data_t) -> *mut obs_data_item_t {
+    if data(data_).items.lock().unwrap().is_empty() {
+        return ptr::null_mut();
+    }
+    obs_data_addref(data_);
+    Box::into_raw(Box::new(ItemCursor {
+        data: data_,
+        index: 0,
+    })) as *mut obs_data_item_t
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_data_item_release(item: *mut *mut obs_data_item_t) {
+    if !(*item).is_null() {
+        let cursor = Box::from_raw(*item as *mut ItemCursor);
+        obs_data_release(cursor.data);
+        *item = ptr::null_mut();
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_data_item_next(item: *mut *mut obs_data_item_t) -> bool {
+    if (*item).is_null() {
+        return false;
+    }
+    let cursor = &mut *(*item as *mut ItemCursor);
+    cursor.index += 1;
+    if cursor.index < data(cursor.data).items.lock().unwrap().len() {
+        true
+    } else {
+        obs_data_item_release(item);
+        false
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_data_item_get_name(item: *mut obs_data_item_t) -> *const c_char {
+    // names never change, so the pointer stays valid while the item exists
+    with_item(item, |item| Some(item.name.as_ptr())).unwrap_or(EMPTY.as_ptr() as *const c_char)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_data_item_gettype(item: *mut obs_data_item_t) -> obs_data_type {
+    with_item(item, |item| {
+        item.values
+            .iter()
+            .filter_map(|v| v.as_ref())
+            .next()
+            .map(|v| v.data_type())
+    }).unwrap_or(obs_data_type_OBS_DATA_NULL)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_data_item_numtype(
+    item: *mut obs_data_item_t,
+) -> obs_data_number_type {
+    with_item(item, |item| {
+        item.values
+            .iter()
+            .filter_map(|v| v.as_ref())
+            .next()
+            .and_then(|v| match *v {
+                Value::Int(_) => Some(obs_data_number_type_OBS_DATA_NUM_INT),
+                Value::Double(_) => Some(obs_data_number_type_OBS_DATA_NUM_DOUBLE),
+                _ => None,
+            })
+    }).unwrap_or(obs_data_number_type_OBS_DATA_NUM_INVALID)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_data_item_has_user_value(item: *mut obs_data_item_t) -> bool {
+    with_item(item, |item| item.values[USER].as_ref().map(|_| ())).is_some()
+}
+
+macro_rules! fake_item_values {
+    ($which:expr, $get_string:ident, $get_int:ident, $get_double:ident, $get_bool:ident,
+     $get_obj:ident) => {
+        #[no_mangle]
+        pub unsafe extern "C" fn $get_string(item: *mut obs_data_item_t) -> *const c_char {
+            with_item(item, |item| $which(item).and_then(get_string))
+                .unwrap_or(EMPTY.as_ptr() as *const c_char)
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn $get_int(item: *mut obs_data_item_t) -> i64 {
+            with_item(item, |item| $which(item).and_then(get_int)).unwrap_or(0)
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn $get_double(item: *mut obs_data_item_t) -> f64 {
+            with_item(item, |item| $which(item).and_then(get_double)).unwrap_or(0.0)
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn $get_bool(item: *mut obs_data_item_t) -> bool {
+            with_item(item, |item| $which(item).and_then(get_bool)).unwrap_or(false)
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn $get_obj(item: *mut obs_data_item_t) -> *mut obs_data_t {
+            with_item(item, |item| $which(item).and_then(|v| get_obj(v)))
+                .unwrap_or(ptr::null_mut())
+        }
+    };
+}
+
+fn item_value(item: &Item) -> Option<&Value> {
+    item.value()
+}
+
+fn item_default(item: &Item) -> Option<&Value> {
+    item.values[DEFAULT].as_ref()
+}
+
+fake_item_values!(
+    item_value,
+    obs_data_item_get_string,
+    obs_data_item_get_int,
+    obs_data_item_get_double,
+    obs_data_item_get_bool,
+    obs_data_item_get_obj
+);
+
+fake_item_values!(
+    item_default,
+    obs_data_item_get_default_string,
+    obs_data_item_get_default_int,
+    obs_data_item_get_default_double,
+    obs_data_item_get_default_bool,
+    obs_data_item_get_default_obj
+);
@@ -0,0 +1,220 @@
+use std::ffi::{CStr, CString};
+use std::mem;
+use std::os::raw::{c_char, c_void};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use super::super::*;
+
+// there's no UI, so tests drive these with the functions at the bottom
+
+struct Callbacks {
+    events: Vec<(obs_frontend_event_cb, usize)>,
+    saves: Vec<(obs_frontend_save_cb, usize)>,
+    tools: Vec<(CString, obs_frontend_cb, usize)>,
+    // holds a reference
+    scene: usize,
+}
+
+lazy_static! {
+    static ref CALLBACKS: Mutex<Callbacks> = Mutex::new(Callbacks {
+        events: Vec::new(),
+        saves: Vec::new(),
+        tools: Vec::new(),
+        scene: 0,
+    });
+}
+
+static RECORDING: AtomicBool = AtomicBool::new(false);
+static STREAMING: AtomicBool = AtomicBool::new(false);
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_frontend_add_event_callback(
+    callback: obs_frontend_event_cb,
+    private_data: *mut c_void,
+) {
+    let mut callbacks = CALLBACKS.lock().unwrap();
+    callbacks.events.push((callback, private_data as usize));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_frontend_remove_event_callback(
+    callback: obs_frontend_event_cb,
+    private_data: *mut c_void,
+) {
+    let mut callbacks = CALLBACKS.lock().unwrap();
+    let private_data = private_data as usize;
+    if let Some(i) = callbacks
+        .events
+        .iter()
+        .position(|&(cb, data)| cb == callback && data == private_data)
+    {
+        callbacks.events.remove(i);
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_frontend_add_save_callback(
+    callback: obs_frontend_save_cb,
+    private_data: *mut c_void,
+) {
+    let mut callbacks = CALLBACKS.lock().unwrap();
+    callbacks.saves.push((callback, private_data as usize));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_frontend_remove_save_callback(
+    callback: obs_frontend_save_cb,
+    private_data: *mut c_void,
+) {
+    let mut callbacks = CALLBACKS.lock().unwrap();
+    let private_data = private_data as usize;
+    if let Some(i) = callbacks
+        .saves
+        .iter()
+        .position(|&(cb, data)| cb == callback && data == private_data)
+    {
+        callbacks.saves.remove(i);
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_frontend_add_tools_menu_item(
+    name: *const c_char,
+    callback: obs_frontend_cb,
+    private_data: *mut c_void,
+) {
+    let mut callbacks = CALLBACKS.lock().unwrap();
+    callbacks
+        .tools
+        .push((CStr::from_ptr(name).to_owned(), callback, private_data as usize));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_frontend_get_current_scene() -> *mut obs_source_t {
+    let callbacks = CALLBACKS.lock().unwrap();
+    let scene = callbacks.scene as *mut obs_source_t;
+    if !scene.is_null() {
+        obs_source_addref(scene);
+    }
+    scene
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_frontend_set_current_scene(scene: *mut obs_source_t) {
+    if !scene.is_null() {
+        obs_source_addref(scene);
+    }
+    let old = {
+        let mut callbacks = CALLBACKS.lock().unwrap();
+        mem::replace(&mut callbacks.scene, scene as usize) as *mut obs_source_t
+    };
+    if !old.is_null() {
+        obs_source_release(old);
+    }
+    emit_frontend_event(obs_frontend_event_OBS_FRONTEND_EVENT_SCENE_CHANGED);
+}
+
+unsafe fn set_output(
+    active: &AtomicBool,
+    value: bool,
+    starting: obs_frontend_event,
+    started: obs_frontend_event,
+) {
+    if active.swap(value, Ordering::SeqCst) != value {
+        emit_frontend_event(starting);
+        emit_frontend_event(started);
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_frontend_recording_start() {
+    set_output(
+        &RECORDING,
+        true,
+        obs_frontend_event_OBS_FRONTEND_EVENT_RECORDING_STARTING,
+        obs_frontend_event_OBS_FRONTEND_EVENT_RECORDING_STARTED,
+    );
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_frontend_recording_stop() {
+    set_output(
+        &RECORDING,
+        false,
+        obs_frontend_event_OBS_FRONTEND_EVENT_RECORDING_STOPPING,
+        obs_frontend_event_OBS_FRONTEND_EVENT_RECORDING_STOPPED,
+    );
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_frontend_recording_active() -> bool {
+    RECORDING.load(Ordering::SeqCst)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_frontend_streaming_start() {
+    set_output(
+        &STREAMING,
+        true,
+        obs_frontend_event_OBS_FRONTEND_EVENT_STREAMING_STARTING,
+        obs_frontend_event_OBS_FRONTEND_EVENT_STREAMING_STARTED,
+    );
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_frontend_streaming_stop() {
+    set_output(
+        &STREAMING,
+        false,
+        obs_frontend_event_OBS_FRONTEND_EVENT_STREAMING_STOPPING,
+        obs_frontend_event_OBS_FRONTEND_EVENT_STREAMING_STOPPED,
+    );
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_frontend_streaming_active() -> bool {
+    STREAMING.load(Ordering::SeqCst)
+}
+
+pub fn emit_frontend_event(event: obs_frontend_event) {
+    // copied out so that callbacks can add and remove callbacks
+    let events = CALLBACKS.lock().unwrap().events.clone();
+    for (callback, data) in events {
+        if let Some(callback) = callback {
+            unsafe {
+                callback(event, data as *mut c_void);
+            }
+        }
+    }
+}
+
+// what OBS does when saving or loading a scene collection
+pub unsafe fn run_save_callbacks(save_data: *mut obs_data_t, saving: bool) {
+    let saves = CALLBACKS.lock().unwrap().saves.clone();
+    for (callback, data) in saves {
+        if let Some(callback) = callback {
+            callback(save_data, saving, data as *mut c_void);
+        }
+    }
+}
+
+// returns false if there's no item called `name`
+pub fn click_tools_menu_item(name: &str) -> bool {
+    let item = CALLBACKS
+        .lock()
+        .unwrap()
+        .tools
+        .iter()
+        .find(|&&(ref item, _, _)| item.to_bytes() == name.as_bytes())
+        .map(|&(_, callback, data)| (callback, data));
+    match item {
+        Some((Some(callback), data)) => {
+            unsafe {
+                callback(data as *mut c_void);
+            }
+            true
+        }
+        Some((None, _)) => true,
+        None => false,
+    }
+}
@@ -0,0 +1,502 @@
+use std::cell::{Cell, RefCell};
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_void};
+use std::ptr;
+use std::slice;
+use super::super::*;
+
+// nothing is drawn, so these only keep track of what they were given
+
+// how deep the pushes and begins go, so tests can check they're all undone
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StackDepths {
+    pub matrix: usize,
+    pub blend: usize,
+    pub texrender: usize,
+    pub technique: usize,
+}
+
+thread_local! {
+    static DEPTHS: Cell<StackDepths> = Cell::new(StackDepths::default());
+}
+
+fn change_depths<F>(f: F)
+where
+    F: FnOnce(&mut StackDepths),
+{
+    DEPTHS.with(|depths| {
+        let mut changed = depths.get();
+        f(&mut changed);
+        depths.set(changed);
+    });
+}
+
+// the matrix, blend, texrender and technique stacks on this thread
+pub fn stack_depths() -> StackDepths {
+    DEPTHS.with(|depths| depths.get())
+}
+
+struct Texture {
+    width: u32,
+    height: u32,
+    format: gs_color_format,
+    dynamic: bool,
+    pixels: Vec<u8>,
+}
+
+fn bytes_per_pixel(format: gs_color_format) -> usize {
+    match format {
+        gs_color_format_GS_A8 | gs_color_format_GS_R8 => 1,
+        gs_color_format_GS_R16 | gs_color_format_GS_R16F => 2,
+        gs_color_format_GS_RGBA16 | gs_color_format_GS_RGBA16F | gs_color_format_GS_RG32F => 8,
+        gs_color_format_GS_RGBA32F => 16,
+        _ => 4,
+    }
+}
+
+unsafe fn create_texture(
+    width: u32,
+    height: u32,
+    format: gs_color_format,
+    data: *const u8,
+    flags: u32,
+) -> Texture {
+    let size = width as usize * height as usize * bytes_per_pixel(format);
+    let pixels = if data.is_null() {
+        vec![0; size]
+    } else {
+        slice::from_raw_parts(data, size).to_vec()
+    };
+    Texture {
+        width,
+        height,
+        format,
+        dynamic: flags & GS_DYNAMIC != 0,
+        pixels,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_texture_create(
+    width: u32,
+    height: u32,
+    color_format: gs_color_format,
+    _levels: u32,
+    data: *mut *const u8,
+    flags: u32,
+) -> *mut gs_texture_t {
+    // the renderers can't make empty textures either
+    if width == 0 || height == 0 {
+        return ptr::null_mut();
+    }
+    // only the first mip level is kept
+    let data = if data.is_null() { ptr::null() } else { *data };
+    let texture = create_texture(width, height, color_format, data, flags);
+    Box::into_raw(Box::new(texture)) as *mut gs_texture_t
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_texture_destroy(tex: *mut gs_texture_t) {
+    if !tex.is_null() {
+        drop(Box::from_raw(tex as *mut Texture));
+    }
+}
+
+// like in OBS, only dynamic textures can be changed
+#[no_mangle]
+pub unsafe extern "C" fn gs_texture_map(
+    tex: *mut gs_texture_t,
+    data: *mut *mut u8,
+    linesize: *mut u32,
+) -> bool {
+    let tex = &mut *(tex as *mut Texture);
+    if !tex.dynamic {
+        return false;
+    }
+    *data = tex.pixels.as_mut_ptr();
+    *linesize = tex.width * bytes_per_pixel(tex.format) as u32;
+    true
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_texture_unmap(_tex: *mut gs_texture_t) {}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_texture_set_image(
+    tex: *mut gs_texture_t,
+    data: *const u8,
+    linesize: u32,
+    invert: bool,
+) {
+    let tex = &mut *(tex as *mut Texture);
+    if !tex.dynamic {
+        return;
+    }
+    let row = tex.width as usize * bytes_per_pixel(tex.format);
+    let height = tex.height as usize;
+    for y in 0..height {
+        let source = if invert { height - y - 1 } else { y };
+        let source = slice::from_raw_parts(data.offset((source * linesize as usize) as isize), row);
+        tex.pixels[y * row..(y + 1) * row].copy_from_slice(source);
+    }
+}
+
+pub unsafe fn texture_size(tex: *mut gs_texture_t) -> (u32, u32) {
+    let tex = &*(tex as *mut Texture);
+    (tex.width, tex.height)
+}
+
+// the pixels of the first mip level, with no padding between rows
+pub unsafe fn texture_data(tex: *mut gs_texture_t) -> Vec<u8> {
+    (*(tex as *mut Texture)).pixels.clone()
+}
+
+struct TextureRender {
+    format: gs_color_format,
+    texture: *mut Texture,
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_texrender_create(
+    format: gs_color_format,
+    _zsformat: gs_zstencil_format,
+) -> *mut gs_texrender_t {
+    Box::into_raw(Box::new(TextureRender {
+        format,
+        texture: ptr::null_mut(),
+    })) as *mut gs_texrender_t
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_texrender_destroy(texrender: *mut gs_texrender_t) {
+    if !texrender.is_null() {
+        let texrender = Box::from_raw(texrender as *mut TextureRender);
+        gs_texture_destroy(texrender.texture as *mut gs_texture_t);
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_texrender_begin(
+    texrender: *mut gs_texrender_t,
+    cx: u32,
+    cy: u32,
+) -> bool {
+    if cx == 0 || cy == 0 {
+        return false;
+    }
+    let texrender = &mut *(texrender as *mut TextureRender);
+    gs_texture_destroy(texrender.texture as *mut gs_texture_t);
+    texrender.texture = Box::into_raw(Box::new(create_texture(
+        cx,
+        cy,
+        texrender.format,
+        ptr::null(),
+        GS_RENDER_TARGET,
+    )));
+    change_depths(|depths| depths.texrender += 1);
+    true
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_texrender_end(_texrender: *mut gs_texrender_t) {
+    change_depths(|depths| depths.texrender = depths.texrender.saturating_sub(1));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_texrender_reset(_texrender: *mut gs_texrender_t) {}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_texrender_get_texture(
+    texrender: *const gs_texrender_t,
+) -> *mut gs_texture_t {
+    (*(texrender as *const TextureRender)).texture as *mut gs_texture_t
+}
+
+enum ParamValue {
+    None,
+    Bool(bool),
+    Int(i32),
+    Float(f32),
+    Texture(*mut gs_texture_t),
+    Val(Vec<u8>),
+}
+
+struct Param {
+    name: CString,
+    value: ParamValue,
+}
+
+struct Effect {
+    // boxed so pointers to them stay put
+    params: RefCell<Vec<Box<Param>>>,
+    // whether gs_effect_loop is part way through a loop
+    looping: Cell<bool>,
+}
+
+fn create_effect() -> *mut gs_effect_t {
+    Box::into_raw(Box::new(Effect {
+        params: RefCell::new(Vec::new()),
+        looping: Cell::new(false),
+    })) as *mut gs_effect_t
+}
+
+// effects aren't compiled, so they never fail
+#[no_mangle]
+pub unsafe extern "C" fn gs_effect_create(
+    _effect_string: *const c_char,
+    _filename: *const c_char,
+    error_string: *mut *mut c_char,
+) -> *mut gs_effect_t {
+    if !error_string.is_null() {
+        *error_string = ptr::null_mut();
+    }
+    create_effect()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_effect_create_from_file(
+    _file: *const c_char,
+    error_string: *mut *mut c_char,
+) -> *mut gs_effect_t {
+    gs_effect_create(ptr::null(), ptr::null(), error_string)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_effect_destroy(effect: *mut gs_effect_t) {
+    if !effect.is_null() {
+        drop(Box::from_raw(effect as *mut Effect));
+    }
+}
+
+// every parameter exists
+#[no_mangle]
+pub unsafe extern "C" fn gs_effect_get_param_by_name(
+    effect: *const gs_effect_t,
+    name: *const c_char,
+) -> *mut gs_eparam_t {
+    let effect = &*(effect as *const Effect);
+    let name = CStr::from_ptr(name);
+    let mut params = effect.params.borrow_mut();
+    if let Some(param) = params.iter_mut().find(|p| p.name.as_c_str() == name) {
+        return &mut **param as *mut Param as *mut gs_eparam_t;
+    }
+    let mut param = Box::new(Param {
+        name: name.to_owned(),
+        value: ParamValue::None,
+    });
+    let raw = &mut *param as *mut Param as *mut gs_eparam_t;
+    params.push(param);
+    raw
+}
+
+// every technique has a single pass
+#[no_mangle]
+pub unsafe extern "C" fn gs_effect_loop(effect: *mut gs_effect_t, _name: *const c_char) -> bool {
+    let effect = &*(effect as *mut Effect);
+    let looping = !effect.looping.get();
+    effect.looping.set(looping);
+    looping
+}
+
+// techniques are the effect itself, with a single pass
+#[no_mangle]
+pub unsafe extern "C" fn gs_effect_get_technique(
+    effect: *const gs_effect_t,
+    _name: *const c_char,
+) -> *mut gs_technique_t {
+    effect as *mut gs_technique_t
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_technique_begin(_technique: *mut gs_technique_t) -> usize {
+    change_depths(|depths| depths.technique += 1);
+    1
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_technique_end(_technique: *mut gs_technique_t) {
+    change_depths(|depths| depths.technique = depths.technique.saturating_sub(1));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_technique_begin_pass(
+    _technique: *mut gs_technique_t,
+    pass: usize,
+) -> bool {
+    pass == 0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_technique_end_pass(_technique: *mut gs_technique_t) {}
+
+unsafe fn set_param(param: *mut gs_eparam_t, value: ParamValue) {
+    if !param.is_null() {
+        (*(param as *mut Param)).value = value;
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_effect_set_bool(param: *mut gs_eparam_t, val: bool) {
+    set_param(param, ParamValue::Bool(val));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_effect_set_int(param: *mut gs_eparam_t, val: i32) {
+    set_param(param, ParamValue::Int(val));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_effect_set_val(
+    param: *mut gs_eparam_t,
+    val: *const c_void,
+    size: usize,
+) {
+    set_param(
+        param,
+        ParamValue::Val(slice::from_raw_parts(val as *const u8, size).to_vec()),
+    );
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_effect_set_default(param: *mut gs_eparam_t) {
+    set_param(param, ParamValue::None);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_effect_set_float(param: *mut gs_eparam_t, val: f32) {
+    set_param(param, ParamValue::Float(val));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_effect_set_texture(param: *mut gs_eparam_t, val: *mut gs_texture_t) {
+    set_param(param, ParamValue::Texture(val));
+}
+
+struct StageSurface {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+// only RGBA is supported
+#[no_mangle]
+pub unsafe extern "C" fn gs_stagesurface_create(
+    width: u32,
+    height: u32,
+    _color_format: gs_color_format,
+) -> *mut gs_stagesurf_t {
+    Box::into_raw(Box::new(StageSurface {
+        width,
+        height,
+        pixels: vec![0; width as usize * height as usize * 4],
+    })) as *mut gs_stagesurf_t
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_stagesurface_destroy(stagesurf: *mut gs_stagesurf_t) {
+    if !stagesurf.is_null() {
+        drop(Box::from_raw(stagesurf as *mut StageSurface));
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_stage_texture(dst: *mut gs_stagesurf_t, src: *mut gs_texture_t) {
+    let dst = &mut *(dst as *mut StageSurface);
+    let src = &*(src as *mut Texture);
+    if (src.width, src.height) == (dst.width, dst.height) && src.pixels.len() == dst.pixels.len() {
+        dst.pixels.copy_from_slice(&src.pixels);
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_stagesurface_map(
+    stagesurf: *mut gs_stagesurf_t,
+    data: *mut *mut u8,
+    linesize: *mut u32,
+) -> bool {
+    let stagesurf = &mut *(stagesurf as *mut StageSurface);
+    *data = stagesurf.pixels.as_mut_ptr();
+    *linesize = stagesurf.width * 4;
+    true
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_stagesurface_unmap(_stagesurf: *mut gs_stagesurf_t) {}
+
+// the base effects are created on first use and never destroyed, like in OBS
+static mut BASE_EFFECT: *mut gs_effect_t = 0 as *mut gs_effect_t;
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_get_base_effect(_effect: obs_base_effect) -> *mut gs_effect_t {
+    if BASE_EFFECT.is_null() {
+        BASE_EFFECT = create_effect();
+    }
+    BASE_EFFECT
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_draw_sprite(
+    _tex: *mut gs_texture_t,
+    _flip: u32,
+    _width: u32,
+    _height: u32,
+) {
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_clear(
+    _clear_flags: u32,
+    _color: *const vec4,
+    _depth: f32,
+    _stencil: u8,
+) {
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_ortho(
+    _left: f32,
+    _right: f32,
+    _top: f32,
+    _bottom: f32,
+    _znear: f32,
+    _zfar: f32,
+) {
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_blend_state_push() {
+    change_depths(|depths| depths.blend += 1);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_blend_state_pop() {
+    change_depths(|depths| depths.blend = depths.blend.saturating_sub(1));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_enable_blending(_enable: bool) {}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_blend_function(_src: gs_blend_type, _dest: gs_blend_type) {}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_matrix_push() {
+    change_depths(|depths| depths.matrix += 1);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_matrix_pop() {
+    change_depths(|depths| depths.matrix = depths.matrix.saturating_sub(1));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_matrix_translate3f(_x: f32, _y: f32, _z: f32) {}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_matrix_scale3f(_x: f32, _y: f32, _z: f32) {}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_matrix_identity() {}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_matrix_rotaa4f(_x: f32, _y: f32, _z: f32, _angle: f32) {}
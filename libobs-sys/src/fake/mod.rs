@@ -0,0 +1,23 @@
+// a stand-in for the parts of libobs the obs wrapper uses, so that it can be
+// tested on machines without OBS or a GPU. everything runs on the CPU and
+// nothing is drawn; textures keep their pixels in memory and the functions
+// below let tests look at what the plugin did.
+//
+// this is not a complete libobs, and only behaves as much like the real thing
+// as the wrapper needs.
+
+mod data;
+#[cfg(feature = "frontend")]
+mod frontend;
+mod graphics;
+mod properties;
+mod signal;
+mod source;
+mod util;
+
+#[cfg(feature = "frontend")]
+pub use self::frontend::{click_tools_menu_item, emit_frontend_event, run_save_callbacks};
+pub use self::graphics::{stack_depths, texture_data, texture_size, StackDepths};
+pub use self::properties::{click_button, modify_property, property_names};
+pub use self::source::{output_audio_frames, registered_source_ids};
+pub use self::util::{render_frame, set_version, take_log};
@@ -0,0 +1,447 @@
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_void};
+use std::ptr;
+use super::super::*;
+use super::source::source_data;
+
+enum ListValue {
+    String(CString),
+    Int(i64),
+    Float(f64),
+}
+
+struct Property {
+    name: CString,
+    description: Option<CString>,
+    long_description: Option<CString>,
+    type_: obs_property_type,
+    visible: bool,
+    enabled: bool,
+    clicked: obs_property_clicked_t,
+    modified: obs_property_modified_t,
+    items: Vec<(CString, ListValue)>,
+    group: *mut obs_properties_t,
+    // the properties this one was added to, which callbacks are given
+    parent: *mut obs_properties_t,
+}
+
+struct Properties {
+    // boxed so pointers to them stay put
+    properties: Vec<Box<Property>>,
+    // set once these are added to other properties as a group
+    parent: *mut obs_properties_t,
+    param: *mut c_void,
+    destroy: Option<unsafe extern "C" fn(param: *mut c_void)>,
+}
+
+unsafe fn properties<'a>(props: *mut obs_properties_t) -> &'a mut Properties {
+    &mut *(props as *mut Properties)
+}
+
+unsafe fn property<'a>(p: *mut obs_property_t) -> &'a mut Property {
+    &mut *(p as *mut Property)
+}
+
+unsafe fn optional_cstring(value: *const c_char) -> Option<CString> {
+    if value.is_null() {
+        None
+    } else {
+        Some(CStr::from_ptr(value).to_owned())
+    }
+}
+
+unsafe fn find(props: *mut obs_properties_t, name: &CStr) -> *mut obs_property_t {
+    if props.is_null() {
+        return ptr::null_mut();
+    }
+    for p in properties(props).properties.iter_mut() {
+        if p.name.as_c_str() == name {
+            return &mut **p as *mut Property as *mut obs_property_t;
+        }
+        let found = find(p.group, name);
+        if !found.is_null() {
+            return found;
+        }
+    }
+    ptr::null_mut()
+}
+
+// names must be unique, so this returns null if `name` is already used
+unsafe fn add(
+    props: *mut obs_properties_t,
+    name: *const c_char,
+    description: *const c_char,
+    type_: obs_property_type,
+) -> *mut obs_property_t {
+    let name = CStr::from_ptr(name);
+    if !find(props, name).is_null() {
+        return ptr::null_mut();
+    }
+    let mut p = Box::new(Property {
+        name: name.to_owned(),
+        description: optional_cstring(description),
+        long_description: None,
+        type_,
+        visible: true,
+        enabled: true,
+        clicked: None,
+        modified: None,
+        items: Vec::new(),
+        group: ptr::null_mut(),
+        parent: props,
+    });
+    let raw = &mut *p as *mut Property as *mut obs_property_t;
+    properties(props).properties.push(p);
+    raw
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_properties_create() -> *mut obs_properties_t {
+    Box::into_raw(Box::new(Properties {
+        properties: Vec::new(),
+        parent: ptr::null_mut(),
+        param: ptr::null_mut(),
+        destroy: None,
+    })) as *mut obs_properties_t
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_properties_destroy(props: *mut obs_properties_t) {
+    if props.is_null() {
+        return;
+    }
+    let props = Box::from_raw(props as *mut Properties);
+    if let Some(destroy) = props.destroy {
+        destroy(props.param);
+    }
+    for p in &props.properties {
+        obs_properties_destroy(p.group);
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_properties_set_param(
+    props: *mut obs_properties_t,
+    param: *mut c_void,
+    destroy: Option<unsafe extern "C" fn(param: *mut c_void)>,
+) {
+    let props = properties(props);
+    if let Some(old_destroy) = props.destroy {
+        if !props.param.is_null() {
+            old_destroy(props.param);
+        }
+    }
+    props.param = param;
+    props.destroy = destroy;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_properties_get_param(props: *mut obs_properties_t) -> *mut c_void {
+    if props.is_null() {
+        ptr::null_mut()
+    } else {
+        properties(props).param
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_properties_get_parent(
+    props: *mut obs_properties_t,
+) -> *mut obs_properties_t {
+    properties(props).parent
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_properties_get(
+    props: *mut obs_properties_t,
+    property: *const c_char,
+) -> *mut obs_property_t {
+    find(props, CStr::from_ptr(property))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_properties_add_bool(
+    props: *mut obs_properties_t,
+    name: *const c_char,
+    description: *const c_char,
+) -> *mut obs_property_t {
+    add(props, name, description, obs_property_type_OBS_PROPERTY_BOOL)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_properties_add_int(
+    props: *mut obs_properties_t,
+    name: *const c_char,
+    description: *const c_char,
+    _min: i32,
+    _max: i32,
+    _step: i32,
+) -> *mut obs_property_t {
+    add(props, name, description, obs_property_type_OBS_PROPERTY_INT)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_properties_add_int_slider(
+    props: *mut obs_properties_t,
+    name: *const c_char,
+    description: *const c_char,
+    _min: i32,
+    _max: i32,
+    _step: i32,
+) -> *mut obs_property_t {
+    add(props, name, description, obs_property_type_OBS_PROPERTY_INT)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_properties_add_float(
+    props: *mut obs_properties_t,
+    name: *const c_char,
+    description: *const c_char,
+    _min: f64,
+    _max: f64,
+    _step: f64,
+) -> *mut obs_property_t {
+    add(props, name, description, obs_property_type_OBS_PROPERTY_FLOAT)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_properties_add_float_slider(
+    props: *mut obs_properties_t,
+    name: *const c_char,
+    description: *const c_char,
+    _min: f64,
+    _max: f64,
+    _step: f64,
+) -> *mut obs_property_t {
+    add(props, name, description, obs_property_type_OBS_PROPERTY_FLOAT)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_properties_add_text(
+    props: *mut obs_properties_t,
+    name: *const c_char,
+    description: *const c_char,
+    _type_: obs_text_type,
+) -> *mut obs_property_t {
+    add(props, name, description, obs_property_type_OBS_PROPERTY_TEXT)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_properties_add_path(
+    props: *mut obs_properties_t,
+    name: *const c_char,
+    description: *const c_char,
+    _type_: obs_path_type,
+    _filter: *const c_char,
+    _default_path: *const c_char,
+) -> *mut obs_property_t {
+    add(props, name, description, obs_property_type_OBS_PROPERTY_PATH)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_properties_add_list(
+    props: *mut obs_properties_t,
+    name: *const c_char,
+    description: *const c_char,
+    _type_: obs_combo_type,
+    _format: obs_combo_format,
+) -> *mut obs_property_t {
+    add(props, name, description, obs_property_type_OBS_PROPERTY_LIST)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_properties_add_color(
+    props: *mut obs_properties_t,
+    name: *const c_char,
+    description: *const c_char,
+) -> *mut obs_property_t {
+    add(props, name, description, obs_property_type_OBS_PROPERTY_COLOR)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_properties_add_button(
+    props: *mut obs_properties_t,
+    name: *const c_char,
+    text: *const c_char,
+    callback: obs_property_clicked_t,
+) -> *mut obs_property_t {
+    let p = add(props, name, text, obs_property_type_OBS_PROPERTY_BUTTON);
+    if !p.is_null() {
+        property(p).clicked = callback;
+    }
+    p
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_properties_add_font(
+    props: *mut obs_properties_t,
+    name: *const c_char,
+    description: *const c_char,
+) -> *mut obs_property_t {
+    add(props, name, description, obs_property_type_OBS_PROPERTY_FONT)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_properties_add_editable_list(
+    props: *mut obs_properties_t,
+    name: *const c_char,
+    description: *const c_char,
+    _type_: obs_editable_list_type,
+    _filter: *const c_char,
+    _default_path: *const c_char,
+) -> *mut obs_property_t {
+    add(
+        props,
+        name,
+        description,
+        obs_property_type_OBS_PROPERTY_EDITABLE_LIST,
+    )
+}
+
+// takes ownership of `group`
+#[no_mangle]
+pub unsafe extern "C" fn obs_properties_add_group(
+    props: *mut obs_properties_t,
+    name: *const c_char,
+    description: *const c_char,
+    _type_: obs_group_type,
+    group: *mut obs_properties_t,
+) -> *mut obs_property_t {
+    let p = add(props, name, description, obs_property_type_OBS_PROPERTY_GROUP);
+    if !p.is_null() {
+        property(p).group = group;
+        properties(group).parent = props;
+    }
+    p
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_property_name(p: *mut obs_property_t) -> *const c_char {
+    property(p).name.as_ptr()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_property_set_visible(p: *mut obs_property_t, visible: bool) {
+    property(p).visible = visible;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_property_set_enabled(p: *mut obs_property_t, enabled: bool) {
+    property(p).enabled = enabled;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_property_set_description(
+    p: *mut obs_property_t,
+    description: *const c_char,
+) {
+    property(p).description = optional_cstring(description);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_property_set_long_description(
+    p: *mut obs_property_t,
+    long_description: *const c_char,
+) {
+    property(p).long_description = optional_cstring(long_description);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_property_set_modified_callback(
+    p: *mut obs_property_t,
+    modified: obs_property_modified_t,
+) {
+    property(p).modified = modified;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_property_list_clear(p: *mut obs_property_t) {
+    property(p).items.clear();
+}
+
+unsafe fn add_item(p: *mut obs_property_t, name: *const c_char, value: ListValue) -> usize {
+    let items = &mut property(p).items;
+    items.push((CStr::from_ptr(name).to_owned(), value));
+    items.len() - 1
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_property_list_add_string(
+    p: *mut obs_property_t,
+    name: *const c_char,
+    val: *const c_char,
+) -> usize {
+    add_item(p, name, ListValue::String(CStr::from_ptr(val).to_owned()))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_property_list_add_int(
+    p: *mut obs_property_t,
+    name: *const c_char,
+    val: i64,
+) -> usize {
+    add_item(p, name, ListValue::Int(val))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_property_list_add_float(
+    p: *mut obs_property_t,
+    name: *const c_char,
+    val: f64,
+) -> usize {
+    add_item(p, name, ListValue::Float(val))
+}
+
+// the names of the properties in the order they were added, including the
+// ones inside groups
+pub unsafe fn property_names(props: *mut obs_properties_t) -> Vec<String> {
+    let mut names = Vec::new();
+    if props.is_null() {
+        return names;
+    }
+    for p in &properties(props).properties {
+        names.push(p.name.to_string_lossy().into_owned());
+        names.extend(property_names(p.group));
+    }
+    names
+}
+
+// presses a button like the properties window would for the properties of
+// `source`, which may be null for the properties of a source type. returns
+// what the callback returned, which is whether the properties should be
+// refreshed.
+pub unsafe fn click_button(
+    props: *mut obs_properties_t,
+    name: &str,
+    source: *mut obs_source_t,
+) -> bool {
+    let p = find(props, &CString::new(name).unwrap());
+    if p.is_null() {
+        return false;
+    }
+    let data = if source.is_null() {
+        ptr::null_mut()
+    } else {
+        source_data(source)
+    };
+    match property(p).clicked {
+        Some(clicked) => clicked(property(p).parent, p, data),
+        None => false,
+    }
+}
+
+// tells a property its value changed, like the properties window would
+pub unsafe fn modify_property(
+    props: *mut obs_properties_t,
+    name: &str,
+    settings: *mut obs_data_t,
+) -> bool {
+    let p = find(props, &CString::new(name).unwrap());
+    if p.is_null() {
+        return false;
+    }
+    match property(p).modified {
+        Some(modified) => modified(property(p).parent, p, settings),
+        None => false,
+    }
+}
@@ -0,0 +1,340 @@
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_void};
+use std::ptr;
+use std::slice;
+use std::sync::Mutex;
+use super::super::*;
+use super::util::fake_bmalloc;
+
+// calldata keeps each parameter as its name and value, both prefixed with
+// their length. only the fake reads it, so it doesn't have to match OBS.
+const LEN_SIZE: usize = 8;
+
+fn push_len(stack: &mut Vec<u8>, len: usize) {
+    for i in 0..LEN_SIZE {
+        stack.push((len >> (i * 8)) as u8);
+    }
+}
+
+fn read_len(bytes: &[u8]) -> usize {
+    bytes[..LEN_SIZE]
+        .iter()
+        .enumerate()
+        .fold(0, |len, (i, &b)| len | ((b as usize) << (i * 8)))
+}
+
+unsafe fn calldata_entries(data: *const calldata_t) -> Vec<(Vec<u8>, Vec<u8>)> {
+    let mut entries = Vec::new();
+    if (*data).stack.is_null() {
+        return entries;
+    }
+    let stack = slice::from_raw_parts((*data).stack, (*data).size);
+    let mut offset = 0;
+    let read = |offset: &mut usize| {
+        let len = read_len(&stack[*offset..]);
+        let value = stack[*offset + LEN_SIZE..*offset + LEN_SIZE + len].to_vec();
+        *offset += LEN_SIZE + len;
+        value
+    };
+    while offset < stack.len() {
+        let name = read(&mut offset);
+        let value = read(&mut offset);
+        entries.push((name, value));
+    }
+    entries
+}
+
+// where the value of `name` starts in the stack, for calldata_get_string
+unsafe fn calldata_value_offset(data: *const calldata_t, name: &[u8]) -> Option<usize> {
+    let mut offset = 0;
+    for (entry_name, value) in calldata_entries(data) {
+        offset += LEN_SIZE + entry_name.len() + LEN_SIZE;
+        if entry_name == name {
+            return Some(offset);
+        }
+        offset += value.len();
+    }
+    None
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn calldata_set_data(
+    data: *mut calldata_t,
+    name: *const c_char,
+    in_: *const c_void,
+    new_size: usize,
+) {
+    let name = CStr::from_ptr(name).to_bytes();
+    let value = if in_.is_null() {
+        Vec::new()
+    } else {
+        slice::from_raw_parts(in_ as *const u8, new_size).to_vec()
+    };
+    let mut stack = Vec::new();
+    let entries = calldata_entries(data)
+        .into_iter()
+        .filter(|&(ref entry_name, _)| entry_name.as_slice() != name)
+        .chain(Some((name.to_vec(), value)));
+    for (entry_name, value) in entries {
+        push_len(&mut stack, entry_name.len());
+        stack.extend_from_slice(&entry_name);
+        push_len(&mut stack, value.len());
+        stack.extend_from_slice(&value);
+    }
+    let new_stack = fake_bmalloc(stack.len()) as *mut u8;
+    ptr::copy_nonoverlapping(stack.as_ptr(), new_stack, stack.len());
+    if !(*data).fixed {
+        bfree((*data).stack as *mut c_void);
+    }
+    (*data).stack = new_stack;
+    (*data).size = stack.len();
+    (*data).capacity = stack.len();
+    (*data).fixed = false;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn calldata_get_data(
+    data: *const calldata_t,
+    name: *const c_char,
+    out: *mut c_void,
+    size: usize,
+) -> bool {
+    let name = CStr::from_ptr(name).to_bytes();
+    match calldata_entries(data)
+        .into_iter()
+        .find(|&(ref entry_name, _)| entry_name.as_slice() == name)
+    {
+        Some((_, ref value)) if value.len() == size => {
+            ptr::copy_nonoverlapping(value.as_ptr(), out as *mut u8, size);
+            true
+        }
+        _ => false,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn calldata_set_string(
+    data: *mut calldata_t,
+    name: *const c_char,
+    str: *const c_char,
+) {
+    if str.is_null() {
+        calldata_set_data(data, name, ptr::null(), 0);
+    } else {
+        let value = CStr::from_ptr(str).to_bytes_with_nul();
+        calldata_set_data(data, name, value.as_ptr() as *const c_void, value.len());
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn calldata_get_string(
+    data: *const calldata_t,
+    name: *const c_char,
+    str: *mut *const c_char,
+) -> bool {
+    match calldata_value_offset(data, CStr::from_ptr(name).to_bytes()) {
+        Some(offset) => {
+            *str = (*data).stack.offset(offset as isize) as *const c_char;
+            true
+        }
+        None => false,
+    }
+}
+
+type Callback = unsafe extern "C" fn(data: *mut c_void, cd: *mut calldata_t);
+
+struct Signal {
+    name: Vec<u8>,
+    callbacks: Vec<(Callback, usize)>,
+}
+
+struct SignalHandler {
+    signals: Mutex<Vec<Signal>>,
+}
+
+// "void name(int param)" -> "name"
+unsafe fn decl_name(decl: *const c_char) -> Vec<u8> {
+    let decl = CStr::from_ptr(decl).to_bytes();
+    let end = decl.iter().position(|&c| c == b'(').unwrap_or(decl.len());
+    let start = decl[..end]
+        .iter()
+        .rposition(|&c| c == b' ')
+        .map_or(0, |space| space + 1);
+    decl[start..end].to_vec()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn signal_handler_create() -> *mut signal_handler_t {
+    Box::into_raw(Box::new(SignalHandler {
+        signals: Mutex::new(Vec::new()),
+    })) as *mut signal_handler_t
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn signal_handler_destroy(handler: *mut signal_handler_t) {
+    if !handler.is_null() {
+        drop(Box::from_raw(handler as *mut SignalHandler));
+    }
+}
+
+unsafe fn signal_handler<'a>(handler: *mut signal_handler_t) -> &'a SignalHandler {
+    &*(handler as *mut SignalHandler)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn signal_handler_add(
+    handler: *mut signal_handler_t,
+    signal_decl: *const c_char,
+) -> bool {
+    let name = decl_name(signal_decl);
+    let mut signals = signal_handler(handler).signals.lock().unwrap();
+    if signals.iter().any(|s| s.name == name) {
+        return false;
+    }
+    signals.push(Signal {
+        name,
+        callbacks: Vec::new(),
+    });
+    true
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn signal_handler_connect(
+    handler: *mut signal_handler_t,
+    signal: *const c_char,
+    callback: Option<Callback>,
+    data: *mut c_void,
+) {
+    let name = CStr::from_ptr(signal).to_bytes();
+    let mut signals = signal_handler(handler).signals.lock().unwrap();
+    match (signals.iter_mut().find(|s| s.name == name), callback) {
+        (Some(signal), Some(callback)) => signal.callbacks.push((callback, data as usize)),
+        (None, _) => blog(
+            LOG_WARNING as i32,
+            b"signal_handler_connect: signal '%s' not found\0".as_ptr() as *const c_char,
+            signal,
+        ),
+        _ => {}
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn signal_handler_disconnect(
+    handler: *mut signal_handler_t,
+    signal: *const c_char,
+    callback: Option<Callback>,
+    data: *mut c_void,
+) {
+    let name = CStr::from_ptr(signal).to_bytes();
+    let callback = match callback {
+        Some(callback) => callback as usize,
+        None => return,
+    };
+    let mut signals = signal_handler(handler).signals.lock().unwrap();
+    if let Some(signal) = signals.iter_mut().find(|s| s.name == name) {
+        if let Some(index) = signal
+            .callbacks
+            .iter()
+            .position(|&(c, d)| c as usize == callback && d == data as usize)
+        {
+            signal.callbacks.remove(index);
+        }
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn signal_handler_signal(
+    handler: *mut signal_handler_t,
+    signal: *const c_char,
+    params: *mut calldata_t,
+) {
+    let name = CStr::from_ptr(signal).to_bytes();
+    // callbacks may connect or disconnect while running
+    let callbacks = signal_handler(handler)
+        .signals
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|s| s.name == name)
+        .map(|s| s.callbacks.clone())
+        .unwrap_or_default();
+    for (callback, data) in callbacks {
+        callback(data as *mut c_void, params);
+    }
+}
+
+struct ProcHandler {
+    procs: Mutex<Vec<(Vec<u8>, Callback, usize)>>,
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn proc_handler_create() -> *mut proc_handler_t {
+    Box::into_raw(Box::new(ProcHandler {
+        procs: Mutex::new(Vec::new()),
+    })) as *mut proc_handler_t
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn proc_handler_destroy(handler: *mut proc_handler_t) {
+    if !handler.is_null() {
+        drop(Box::from_raw(handler as *mut ProcHandler));
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn proc_handler_add(
+    handler: *mut proc_handler_t,
+    decl_string: *const c_char,
+    proc_: Option<Callback>,
+    data: *mut c_void,
+) {
+    if let Some(proc_) = proc_ {
+        (*(handler as *mut ProcHandler))
+            .procs
+            .lock()
+            .unwrap()
+            .push((decl_name(decl_string), proc_, data as usize));
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn proc_handler_call(
+    handler: *mut proc_handler_t,
+    name: *const c_char,
+    params: *mut calldata_t,
+) -> bool {
+    let name = CStr::from_ptr(name).to_bytes();
+    let found = (*(handler as *mut ProcHandler))
+        .procs
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|p| p.0 == name)
+        .map(|p| (p.1, p.2));
+    match found {
+        Some((proc_, data)) => {
+            proc_(data as *mut c_void, params);
+            true
+        }
+        None => false,
+    }
+}
+
+lazy_static! {
+    static ref GLOBAL_HANDLERS: (usize, usize) = unsafe {
+        (
+            signal_handler_create() as usize,
+            proc_handler_create() as usize,
+        )
+    };
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_get_signal_handler() -> *mut signal_handler_t {
+    GLOBAL_HANDLERS.0 as *mut signal_handler_t
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_get_proc_handler() -> *mut proc_handler_t {
+    GLOBAL_HANDLERS.1 as *mut proc_handler_t
+}
@@ -0,0 +1,403 @@
+use std::ffi::{CStr, CString};
+use std::mem;
+use std::os::raw::{c_char, c_void};
+use std::ptr;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use super::super::*;
+use super::signal::{proc_handler_create, proc_handler_destroy, signal_handler_create,
+                    signal_handler_destroy};
+
+struct SourceType(obs_source_info);
+
+// the pointers in obs_source_info are only used on the thread OBS would use
+unsafe impl Send for SourceType {}
+
+lazy_static! {
+    static ref SOURCE_TYPES: Mutex<Vec<SourceType>> = Mutex::new(Vec::new());
+}
+
+unsafe fn find_type(id: *const c_char) -> Option<obs_source_info> {
+    let id = CStr::from_ptr(id);
+    SOURCE_TYPES
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|t| CStr::from_ptr(t.0.id) == id)
+        .map(|t| t.0)
+}
+
+// what weak sources point to. it outlives the source so that upgrading can
+// tell whether the source is gone.
+struct WeakSource {
+    refs: AtomicUsize,
+    source: Mutex<*mut Source>,
+}
+
+struct Source {
+    info: obs_source_info,
+    settings: *mut obs_data_t,
+    // what `info.create` returned
+    data: *mut c_void,
+    refs: AtomicUsize,
+    name: CString,
+    weak: *mut WeakSource,
+    signals: *mut signal_handler_t,
+    procs: *mut proc_handler_t,
+    audio_frames: AtomicUsize,
+}
+
+unsafe fn source<'a>(source: *mut obs_source_t) -> &'a Source {
+    &*(source as *mut Source)
+}
+
+// what OBS gives button callbacks, like `context->data` in OBS
+pub(super) unsafe fn source_data(source_: *mut obs_source_t) -> *mut c_void {
+    source(source_).data
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_register_source_s(info: *const obs_source_info, size: usize) {
+    // older plugins may pass a smaller struct; the rest stays zeroed
+    let mut copy: obs_source_info = mem::zeroed();
+    ptr::copy_nonoverlapping(
+        info as *const u8,
+        &mut copy as *mut obs_source_info as *mut u8,
+        size.min(mem::size_of::<obs_source_info>()),
+    );
+    if find_type(copy.id).is_some() {
+        blog(
+            LOG_ERROR as i32,
+            b"Source '%s' already exists!\0".as_ptr() as *const c_char,
+            copy.id,
+        );
+        return;
+    }
+    SOURCE_TYPES.lock().unwrap().push(SourceType(copy));
+}
+
+// the ids of every registered source type, in the order they were registered
+pub fn registered_source_ids() -> Vec<String> {
+    SOURCE_TYPES
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|t| unsafe { CStr::from_ptr(t.0.id).to_string_lossy().into_owned() })
+        .collect()
+}
+
+unsafe fn get_defaults(info: &obs_source_info, settings: *mut obs_data_t) {
+    if let Some(get_defaults2) = info.get_defaults2 {
+        get_defaults2(info.type_data, settings);
+    } else if let Some(get_defaults) = info.get_defaults {
+        get_defaults(settings);
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_source_create_private(
+    id: *const c_char,
+    name: *const c_char,
+    settings: *mut obs_data_t,
+) -> *mut obs_source_t {
+    let info = match find_type(id) {
+        Some(info) => info,
+        None => return ptr::null_mut(),
+    };
+    let source_settings = obs_data_create();
+    get_defaults(&info, source_settings);
+    obs_data_apply(source_settings, settings);
+    let weak = Box::into_raw(Box::new(WeakSource {
+        refs: AtomicUsize::new(1),
+        source: Mutex::new(ptr::null_mut()),
+    }));
+    let raw = Box::into_raw(Box::new(Source {
+        info,
+        settings: source_settings,
+        data: ptr::null_mut(),
+        refs: AtomicUsize::new(1),
+        name: if name.is_null() {
+            CString::default()
+        } else {
+            CStr::from_ptr(name).to_owned()
+        },
+        weak,
+        signals: signal_handler_create(),
+        procs: proc_handler_create(),
+        audio_frames: AtomicUsize::new(0),
+    }));
+    *(*weak).source.lock().unwrap() = raw;
+    if let Some(create) = info.create {
+        let data = create((*raw).settings, raw as *mut obs_source_t);
+        (*raw).data = data;
+        if data.is_null() {
+            blog(
+                LOG_ERROR as i32,
+                b"Failed to create source '%s'!\0".as_ptr() as *const c_char,
+                (*raw).name.as_ptr(),
+            );
+        }
+    }
+    raw as *mut obs_source_t
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_source_addref(source: *mut obs_source_t) {
+    if !source.is_null() {
+        (*(source as *mut Source))
+            .refs
+            .fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+unsafe fn release_weak(weak: *mut WeakSource) {
+    if (*weak).refs.fetch_sub(1, Ordering::SeqCst) == 1 {
+        drop(Box::from_raw(weak));
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_source_release(source: *mut obs_source_t) {
+    if source.is_null() {
+        return;
+    }
+    let raw = source as *mut Source;
+    {
+        // hold the lock so weak sources can't revive us half way through
+        let mut guard = (*(*raw).weak).source.lock().unwrap();
+        if (*raw).refs.fetch_sub(1, Ordering::SeqCst) != 1 {
+            return;
+        }
+        *guard = ptr::null_mut();
+    }
+    let source = Box::from_raw(raw);
+    let data = source.data;
+    if !data.is_null() {
+        if let Some(destroy) = source.info.destroy {
+            destroy(data);
+        }
+    }
+    obs_data_release(source.settings);
+    signal_handler_destroy(source.signals);
+    proc_handler_destroy(source.procs);
+    release_weak(source.weak);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_source_get_weak_source(
+    source_: *mut obs_source_t,
+) -> *mut obs_weak_source_t {
+    let weak = source(source_).weak;
+    (*weak).refs.fetch_add(1, Ordering::SeqCst);
+    weak as *mut obs_weak_source_t
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_weak_source_addref(weak: *mut obs_weak_source_t) {
+    (*(weak as *mut WeakSource))
+        .refs
+        .fetch_add(1, Ordering::SeqCst);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_weak_source_release(weak: *mut obs_weak_source_t) {
+    release_weak(weak as *mut WeakSource);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_weak_source_get_source(
+    weak: *mut obs_weak_source_t,
+) -> *mut obs_source_t {
+    let guard = (*(weak as *mut WeakSource)).source.lock().unwrap();
+    if guard.is_null() {
+        return ptr::null_mut();
+    }
+    (**guard).refs.fetch_add(1, Ordering::SeqCst);
+    *guard as *mut obs_source_t
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_source_get_id(source_: *const obs_source_t) -> *const c_char {
+    source(source_ as *mut obs_source_t).info.id
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_source_get_name(source_: *mut obs_source_t) -> *const c_char {
+    source(source_).name.as_ptr()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_source_update(source_: *mut obs_source_t, settings: *mut obs_data_t) {
+    let source = source(source_);
+    obs_data_apply(source.settings, settings);
+    let data = source.data;
+    if let (false, Some(update)) = (data.is_null(), source.info.update) {
+        update(data, source.settings);
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_source_get_width(source_: *mut obs_source_t) -> u32 {
+    let source = source(source_);
+    let data = source.data;
+    match (data.is_null(), source.info.get_width) {
+        (false, Some(get_width)) => get_width(data),
+        _ => 0,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_source_get_height(source_: *mut obs_source_t) -> u32 {
+    let source = source(source_);
+    let data = source.data;
+    match (data.is_null(), source.info.get_height) {
+        (false, Some(get_height)) => get_height(data),
+        _ => 0,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_source_video_render(source_: *mut obs_source_t) {
+    let source = source(source_);
+    let data = source.data;
+    if let (false, Some(video_render)) = (data.is_null(), source.info.video_render) {
+        video_render(data, ptr::null_mut());
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_source_properties(
+    source_: *mut obs_source_t,
+) -> *mut obs_properties_t {
+    let source = source(source_);
+    let data = source.data;
+    match (data.is_null(), source.info.get_properties) {
+        (false, Some(get_properties)) => get_properties(data),
+        _ => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_source_get_signal_handler(
+    source_: *mut obs_source_t,
+) -> *mut signal_handler_t {
+    source(source_).signals
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_source_get_proc_handler(
+    source_: *mut obs_source_t,
+) -> *mut proc_handler_t {
+    source(source_).procs
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_source_output_audio(
+    source_: *mut obs_source_t,
+    audio: *const obs_source_audio,
+) {
+    source(source_)
+        .audio_frames
+        .fetch_add((*audio).frames as usize, Ordering::SeqCst);
+}
+
+// how many frames of audio have been output by `source`
+pub unsafe fn output_audio_frames(source_: *mut obs_source_t) -> usize {
+    source(source_).audio_frames.load(Ordering::SeqCst)
+}
+
+// filters are never attached to anything here
+#[no_mangle]
+pub unsafe extern "C" fn obs_filter_get_parent(_filter: *mut obs_source_t) -> *mut obs_source_t {
+    ptr::null_mut()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_filter_get_target(_filter: *mut obs_source_t) -> *mut obs_source_t {
+    ptr::null_mut()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_source_process_filter_begin(
+    _filter: *mut obs_source_t,
+    _format: gs_color_format,
+    _allow_direct: obs_allow_direct_render,
+) -> bool {
+    false
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_source_process_filter_end(
+    _filter: *mut obs_source_t,
+    _effect: *mut gs_effect_t,
+    _width: u32,
+    _height: u32,
+) {
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_source_skip_video_filter(_filter: *mut obs_source_t) {}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_source_draw(
+    _image: *mut gs_texture_t,
+    _x: i32,
+    _y: i32,
+    _cx: u32,
+    _cy: u32,
+    _flip: bool,
+) {
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_get_source_defaults(id: *const c_char) -> *mut obs_data_t {
+    match find_type(id) {
+        Some(info) => {
+            let settings = obs_data_create();
+            get_defaults(&info, settings);
+            settings
+        }
+        None => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_get_source_properties(id: *const c_char) -> *mut obs_properties_t {
+    match find_type(id).and_then(|info| info.get_properties) {
+        Some(get_properties) => get_properties(ptr::null_mut()),
+        None => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_get_source_output_flags(id: *const c_char) -> u32 {
+    find_type(id).map_or(0, |info| info.output_flags)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_source_get_display_name(id: *const c_char) -> *const c_char {
+    match find_type(id) {
+        Some(obs_source_info {
+            get_name: Some(get_name),
+            type_data,
+            ..
+        }) => get_name(type_data),
+        _ => ptr::null(),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_enum_input_types(idx: usize, id: *mut *const c_char) -> bool {
+    let types = SOURCE_TYPES.lock().unwrap();
+    match types
+        .iter()
+        .filter(|t| t.0.type_ == obs_source_type_OBS_SOURCE_TYPE_INPUT)
+        .nth(idx)
+    {
+        Some(t) => {
+            *id = t.0.id;
+            true
+        }
+        None => false,
+    }
+}
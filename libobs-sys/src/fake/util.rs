@@ -0,0 +1,153 @@
+use std::ffi::CStr;
+use std::mem;
+use std::os::raw::{c_char, c_void};
+use std::ptr;
+use std::sync::Mutex;
+use std::time::Instant;
+use super::super::*;
+
+// bmalloc'd memory is a Vec<u64> with its length in front of the pointer we
+// hand out, so bfree can rebuild the Vec and drop it
+const HEADER: usize = 2;
+
+pub unsafe fn fake_bmalloc(size: usize) -> *mut c_void {
+    let len = HEADER + (size + 7) / 8;
+    let mut memory = vec![0u64; len];
+    memory[0] = len as u64;
+    let ptr = memory.as_mut_ptr();
+    mem::forget(memory);
+    ptr.offset(HEADER as isize) as *mut c_void
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn bfree(ptr: *mut c_void) {
+    if ptr.is_null() {
+        return;
+    }
+    let memory = (ptr as *mut u64).offset(-(HEADER as isize));
+    let len = *memory as usize;
+    drop(Vec::from_raw_parts(memory, len, len));
+}
+
+lazy_static! {
+    static ref LOG: Mutex<Vec<(i32, String)>> = Mutex::new(Vec::new());
+    static ref START: Instant = Instant::now();
+    static ref RENDER_CALLBACKS: Mutex<Vec<(RenderCallback, usize)>> = Mutex::new(Vec::new());
+    static ref VERSION: Mutex<u32> = Mutex::new(
+        (LIBOBS_API_MAJOR_VER << 24) | (LIBOBS_API_MINOR_VER << 16) | LIBOBS_API_PATCH_VER
+    );
+}
+
+// called by blog in blog.c. messages are only kept for `take_log`, so tests
+// decide what to look at instead of everything landing on stderr.
+#[no_mangle]
+pub unsafe extern "C" fn fake_blog_message(log_level: i32, message: *const c_char) {
+    let message = CStr::from_ptr(message).to_string_lossy().into_owned();
+    LOG.lock().unwrap().push((log_level, message));
+}
+
+// everything logged since the last call, with its level
+pub fn take_log() -> Vec<(i32, String)> {
+    mem::replace(&mut *LOG.lock().unwrap(), Vec::new())
+}
+
+// pretends to be another version of OBS, packed like obs_get_version. it
+// starts out as the version of the headers.
+pub fn set_version(major: u32, minor: u32, patch: u32) {
+    *VERSION.lock().unwrap() = (major << 24) | (minor << 16) | patch;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_get_version() -> u32 {
+    *VERSION.lock().unwrap()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn os_gettime_ns() -> u64 {
+    let elapsed = START.elapsed();
+    elapsed.as_secs() * 1_000_000_000 + u64::from(elapsed.subsec_nanos())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_module_load_locale(
+    _module: *mut obs_module_t,
+    _default_locale: *const c_char,
+    _locale: *const c_char,
+) -> *mut lookup_t {
+    ptr::null_mut()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn text_lookup_getstr(
+    _lookup: *mut lookup_t,
+    _lookup_val: *const c_char,
+    _out: *mut *const c_char,
+) -> bool {
+    false
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn text_lookup_destroy(_lookup: *mut lookup_t) {}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_get_audio_info(info: *mut obs_audio_info) -> bool {
+    (*info).samples_per_sec = 48000;
+    (*info).speakers = speaker_layout_SPEAKERS_STEREO;
+    true
+}
+
+// the graphics "context" only has to be non-null
+static mut GRAPHICS: u8 = 0;
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_enter_graphics() {}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_leave_graphics() {}
+
+#[no_mangle]
+pub unsafe extern "C" fn gs_get_context() -> *mut graphics_t {
+    &mut GRAPHICS as *mut u8 as *mut graphics_t
+}
+
+type RenderCallback = unsafe extern "C" fn(param: *mut c_void, cx: u32, cy: u32);
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_add_main_render_callback(
+    draw: Option<RenderCallback>,
+    param: *mut c_void,
+) {
+    if let Some(draw) = draw {
+        RENDER_CALLBACKS
+            .lock()
+            .unwrap()
+            .push((draw, param as usize));
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn obs_remove_main_render_callback(
+    draw: Option<RenderCallback>,
+    param: *mut c_void,
+) {
+    if let Some(draw) = draw {
+        let mut callbacks = RENDER_CALLBACKS.lock().unwrap();
+        if let Some(index) = callbacks
+            .iter()
+            .position(|&(d, p)| d as usize == draw as usize && p == param as usize)
+        {
+            callbacks.remove(index);
+        }
+    }
+}
+
+// runs the main render callbacks like OBS does once per frame
+pub fn render_frame(cx: u32, cy: u32) {
+    // callbacks may add or remove callbacks while running
+    let callbacks = RENDER_CALLBACKS.lock().unwrap().clone();
+    for (draw, param) in callbacks {
+        unsafe {
+            draw(param as *mut c_void, cx, cy);
+        }
+    }
+}
@@ -4,3 +4,12 @@
 #![allow(dead_code)]
 
 include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+
+#[cfg(feature = "fake")]
+#[macro_use]
+extern crate lazy_static;
+#[cfg(feature = "fake")]
+extern crate serde_json;
+
+#[cfg(feature = "fake")]
+pub mod fake;
@@ -0,0 +1,292 @@
+// derives for mapping Rust types to OBS settings. the generated code refers
+// to `::obs`, so the crate using them needs `extern crate obs` at its root,
+// unless the type says where to find it with `#[obs(crate_path = "...")]`.
+
+extern crate proc_macro;
+#[macro_use]
+extern crate quote;
+extern crate syn;
+
+use proc_macro::TokenStream;
+use quote::Tokens;
+use syn::{Attribute, Data, DeriveInput, Fields, Lit, Meta, NestedMeta, Path};
+
+#[derive(Default)]
+struct Options {
+    crate_path: Option<String>,
+    key: Option<String>,
+    label: Option<String>,
+    value: Option<String>,
+    default: Option<Lit>,
+    default_trait: bool,
+    min: Option<Lit>,
+    max: Option<Lit>,
+    step: Option<Lit>,
+    slider: bool,
+    multiline: bool,
+}
+
+fn lit_str(name: &str, lit: &Lit) -> String {
+    match *lit {
+        Lit::Str(ref s) => s.value(),
+        _ => panic!("obs attribute {} must be a string", name),
+    }
+}
+
+// reads everything from #[obs(...)]
+fn parse_options(attrs: &[Attribute]) -> Options {
+    let mut options = Options::default();
+    for attr in attrs {
+        let list = match attr.interpret_meta() {
+            Some(Meta::List(list)) => list,
+            _ => continue,
+        };
+        if list.ident.to_string() != "obs" {
+            continue;
+        }
+        for nested in list.nested.iter() {
+            match *nested {
+                NestedMeta::Meta(Meta::NameValue(ref pair)) => {
+                    let name = pair.ident.to_string();
+                    let lit = pair.lit.clone();
+                    match name.as_str() {
+                        "crate_path" => options.crate_path = Some(lit_str(&name, &lit)),
+                        "key" => options.key = Some(lit_str(&name, &lit)),
+                        "label" => options.label = Some(lit_str(&name, &lit)),
+                        "value" => options.value = Some(lit_str(&name, &lit)),
+                        "default" => options.default = Some(lit),
+                        "min" => options.min = Some(lit),
+                        "max" => options.max = Some(lit),
+                        "step" => options.step = Some(lit),
+                        _ => panic!("unknown obs attribute {}", name),
+                    }
+                }
+                NestedMeta::Meta(Meta::Word(ref word)) => match word.to_string().as_str() {
+                    "default" => options.default_trait = true,
+                    "slider" => options.slider = true,
+                    "multiline" => options.multiline = true,
+                    other => panic!("unknown obs attribute {}", other),
+                },
+                _ => panic!("unsupported obs attribute"),
+            }
+        }
+    }
+    options
+}
+
+// the path to the obs crate, from the attributes of the type
+fn crate_path(attrs: &[Attribute]) -> Tokens {
+    match parse_options(attrs).crate_path {
+        Some(path) => {
+            let path: Path = syn::parse_str(&path)
+                .unwrap_or_else(|_| panic!("obs attribute crate_path must be a path"));
+            quote! { #path }
+        }
+        None => quote! { ::obs },
+    }
+}
+
+// fields and variants can't move the obs crate
+fn field_options(attrs: &[Attribute]) -> Options {
+    let options = parse_options(attrs);
+    if options.crate_path.is_some() {
+        panic!("obs attribute crate_path only goes on the type");
+    }
+    options
+}
+
+fn optional_number(lit: &Option<Lit>) -> Tokens {
+    match *lit {
+        Some(ref lit) => quote! { ::std::option::Option::Some((#lit) as f64) },
+        None => quote! { ::std::option::Option::None },
+    }
+}
+
+// #[derive(Settings)] on a struct with named fields. every field type must
+// implement `obs::SettingsValue`.
+//
+// struct attributes:
+// - `crate_path = "..."`: where the obs crate is, defaulting to `::obs`
+//
+// field attributes:
+// - `key = "..."`: the settings key, defaulting to the field name
+// - `label = "..."`: looked up in the plugin's locale, defaulting to the key
+// - `default = ...`: a literal default value
+// - `default`: use `Default::default()` as the default value
+// - `min = ...`, `max = ...`, `step = ...`: limits for numbers
+// - `slider`: show numbers as a slider
+// - `multiline`: show strings as a multiline text box
+#[proc_macro_derive(Settings, attributes(obs))]
+pub fn derive_settings(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = syn::parse(input).unwrap();
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let obs = crate_path(&input.attrs);
+    let fields = match input.data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => fields.named.iter().collect::<Vec<_>>(),
+            _ => panic!("Settings can only be derived for structs with named fields"),
+        },
+        _ => panic!("Settings can only be derived for structs"),
+    };
+
+    let mut reads = Vec::new();
+    let mut writes = Vec::new();
+    let mut defaults = Vec::new();
+    let mut properties = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        let options = field_options(&field.attrs);
+        let key = options.key.clone().unwrap_or_else(|| ident.to_string());
+        let label = options.label.clone().unwrap_or_else(|| key.clone());
+
+        reads.push(quote! {
+            #ident: <#ty as #obs::SettingsValue>::get_value(data, #key),
+        });
+        writes.push(quote! {
+            #obs::SettingsValue::set_value(&self.#ident, data, #key);
+        });
+        let default = match options.default {
+            // strings need converting from &str
+            Some(ref lit @ Lit::Str(_)) => Some(quote! { ::std::convert::From::from(#lit) }),
+            Some(ref lit) => Some(quote! { #lit }),
+            None if options.default_trait => Some(quote! { ::std::default::Default::default() }),
+            None => None,
+        };
+        if let Some(default) = default {
+            defaults.push(quote! {
+                {
+                    let value: #ty = #default;
+                    #obs::SettingsValue::set_default_value(&value, data, #key);
+                }
+            });
+        }
+        let min = optional_number(&options.min);
+        let max = optional_number(&options.max);
+        let step = optional_number(&options.step);
+        let slider = options.slider;
+        let multiline = options.multiline;
+        properties.push(quote! {
+            <#ty as #obs::SettingsValue>::add_property(
+                props,
+                #key,
+                &#obs::module_text(#label),
+                &#obs::PropertyOptions {
+                    min: #min,
+                    max: #max,
+                    step: #step,
+                    slider: #slider,
+                    multiline: #multiline,
+                },
+            );
+        });
+    }
+
+    let expanded = quote! {
+        impl #impl_generics #obs::Settings for #name #ty_generics #where_clause {
+            fn from_data(data: &#obs::Data) -> Self {
+                #name {
+                    #(#reads)*
+                }
+            }
+            fn to_data(&self, data: &mut #obs::Data) {
+                #(#writes)*
+            }
+            fn set_defaults(data: &mut #obs::Data) {
+                #(#defaults)*
+            }
+            fn add_properties(props: &mut #obs::Properties) {
+                #(#properties)*
+            }
+        }
+    };
+    expanded.into()
+}
+
+// #[derive(SettingsValue)] on an enum without fields. the value is stored as
+// a string and shown as a combo box.
+//
+// enum attributes:
+// - `crate_path = "..."`: where the obs crate is, defaulting to `::obs`
+//
+// variant attributes:
+// - `value = "..."`: the stored value, defaulting to the lowercase name
+// - `label = "..."`: looked up in the plugin's locale, defaulting to the name
+//
+// unknown values read as the first variant.
+#[proc_macro_derive(SettingsValue, attributes(obs))]
+pub fn derive_settings_value(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = syn::parse(input).unwrap();
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let obs = crate_path(&input.attrs);
+    let variants = match input.data {
+        Data::Enum(ref data) => data.variants.iter().collect::<Vec<_>>(),
+        _ => panic!("SettingsValue can only be derived for enums"),
+    };
+    if variants.is_empty() {
+        panic!("SettingsValue needs at least one variant");
+    }
+
+    let mut reads = Vec::new();
+    let mut values = Vec::new();
+    let mut items = Vec::new();
+    for variant in &variants {
+        match variant.fields {
+            Fields::Unit => {}
+            _ => panic!("SettingsValue can only be derived for enums without fields"),
+        }
+        let ident = &variant.ident;
+        let options = field_options(&variant.attrs);
+        let value = options
+            .value
+            .clone()
+            .unwrap_or_else(|| ident.to_string().to_lowercase());
+        let label = options.label.clone().unwrap_or_else(|| ident.to_string());
+        reads.push(quote! {
+            ::std::option::Option::Some(#value) => #name::#ident,
+        });
+        values.push(quote! {
+            #name::#ident => #value,
+        });
+        items.push(quote! {
+            list.add_string_item(&#obs::module_text(#label), #value);
+        });
+    }
+    let first = &variants[0].ident;
+    let default_values = values.clone();
+
+    let expanded = quote! {
+        impl #impl_generics #obs::SettingsValue for #name #ty_generics #where_clause {
+            fn get_value(data: &#obs::Data, key: &str) -> Self {
+                match data.get_string(key).as_ref().map(|s| s.as_str()) {
+                    #(#reads)*
+                    _ => #name::#first,
+                }
+            }
+            fn set_value(&self, data: &mut #obs::Data, key: &str) {
+                let value = match *self {
+                    #(#values)*
+                };
+                data.set_string(key, value);
+            }
+            fn set_default_value(&self, data: &mut #obs::Data, key: &str) {
+                let value = match *self {
+                    #(#default_values)*
+                };
+                data.set_default_string(key, value);
+            }
+            fn add_property(
+                props: &mut #obs::Properties,
+                key: &str,
+                label: &str,
+                _options: &#obs::PropertyOptions,
+            ) {
+                let mut list = props.add_string_list(key, label);
+                #(#items)*
+            }
+        }
+    };
+    expanded.into()
+}
@@ -1,12 +1,14 @@
 use {Client, ClientAccess, ClientId};
+use autohide::{self, Autohide, Track};
 use futures::prelude::*;
 use futures::future;
 use hyper::{self, mime, Method, Request, Response, StatusCode, Uri};
 use hyper::header::{q, Accept, ContentLength, ContentType, QualityItem};
 use hyper_tls::HttpsConnector;
 use image::{self, ImageFormat, RgbaImage};
-use obs::{self, Data, ObsSource, Texture, VideoSource, VideoSourceDefinition};
-use std::cell::RefCell;
+use obs::{ColorFormat, Data, GraphicsQueue, ObsSource, Properties, RenderCell, RenderToken,
+          Texture, VideoSource, VideoSourceDefinition};
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 use std::str::FromStr;
 use std::sync::{Arc, Mutex, Weak};
@@ -116,22 +118,48 @@ pub(super) struct AlbumArtSourceDefinition {
     client: Mutex<Weak<ArtClient>>,
 }
 
-// used to deal with sending data to a thread where we do not own the stack.
-// Mutex would work without having to hack anything, but then the mutex needs
-// to be obtained on the render thread three times per album art source per frame.
-struct UnsafeSync<T>(T);
-
-unsafe impl<T> Sync for UnsafeSync<T> {}
-
 struct ArtData {
     is_playing: bool,
+    // kept when there's no art so it can be reused for the next track
     texture: Option<Texture>,
+    // whether `texture` holds the art for the current track
+    has_art: bool,
+    // incremented every time the track changes
+    track: u64,
+}
+
+// album art is usually the same size from one track to the next, so the
+// texture is kept and overwritten instead of being recreated every time.
+// returns true if the texture now holds `image`.
+fn update_texture(texture: &mut Option<Texture>, image: &RgbaImage, token: &RenderToken) -> bool {
+    let (width, height) = image.dimensions();
+    let reusable = texture.as_ref().map_or(false, |texture| {
+        texture.is_dynamic() && texture.width() == width && texture.height() == height
+    });
+    if !reusable {
+        *texture = match Texture::new_dynamic(width, height, ColorFormat::Rgba, token) {
+            Ok(texture) => Some(texture),
+            Err(e) => {
+                error!("failed to create album art texture: {}", e);
+                None
+            }
+        };
+    }
+    match *texture {
+        Some(ref mut texture) => match texture.set_image(image, token) {
+            Ok(()) => true,
+            Err(e) => {
+                error!("failed to update album art: {}", e);
+                false
+            }
+        },
+        None => false,
+    }
 }
 
 struct ArtClient {
     _client: Client,
-    // only access from the render thread!
-    data: Arc<UnsafeSync<RefCell<ArtData>>>,
+    data: Arc<RenderCell<ArtData>>,
 }
 
 impl AlbumArtSourceDefinition {
@@ -145,21 +173,36 @@ impl AlbumArtSourceDefinition {
 
 impl VideoSourceDefinition for AlbumArtSourceDefinition {
     type Source = AlbumArtSource;
-    fn create(&self, _settings: &Data, _source: &mut ObsSource) -> Self::Source {
+    fn create(&self, settings: &Data, _source: &mut ObsSource) -> Self::Source {
         let mut guard = self.client.lock().unwrap();
         let art_client = match guard.upgrade() {
             Some(art_client) => Some(art_client),
             None => {
-                let data = Arc::new(UnsafeSync(RefCell::new(ArtData {
+                let data = Arc::new(RenderCell::new(ArtData {
                     is_playing: false,
                     texture: None,
-                })));
+                    has_art: false,
+                    track: 0,
+                }));
                 let art_address: RefCell<Option<String>> = RefCell::new(None);
+                let last_track: RefCell<Track> = RefCell::new(Track::default());
+                let track_count = Cell::new(0u64);
                 let update_data = data.clone();
+                let queue = Arc::new(GraphicsQueue::new());
                 let client = self.client_access.client(&ClientId::Art, move |s, handle| {
                     let is_playing = s.is_playing;
+                    {
+                        let mut last_track = last_track.borrow_mut();
+                        let current = autohide::to_track(s.track.as_ref());
+                        if autohide::track_changed(&last_track, &current) {
+                            track_count.set(track_count.get() + 1);
+                        }
+                        *last_track = current;
+                    }
+                    let track = track_count.get();
                     let mut art_address = art_address.borrow_mut();
                     let update_data = update_data.clone();
+                    let queue = queue.clone();
                     let address = s.track
                         .as_ref()
                         .and_then(|s| s.album_art.as_ref())
@@ -171,30 +214,32 @@ impl VideoSourceDefinition for AlbumArtSourceDefinition {
                             let err_address = address.to_string();
                             Box::new(
                                 load(address, handle)
-                                    .and_then(move |image| unsafe {
-                                        let _graphics = obs::enter_graphics();
-                                        future::ok(Some(Some(Texture::new(&image))))
-                                    })
+                                    .map(|image| Some(Some(image)))
                                     .or_else(move |err| {
-                                        println!(
+                                        warn!(
                                             "failed to load art from {}: {:?}",
                                             err_address, err
                                         );
                                         future::ok(Some(None))
                                     }),
                             )
-                                as Box<Future<Item = Option<Option<Texture>>, Error = ()>>
+                                as Box<Future<Item = Option<Option<RgbaImage>>, Error = ()>>
                         }
                     }.and_then(move |image| {
                         let update_data = update_data.clone();
-                        Box::new(obs::execute_main_render_callback(move |_, _| {
-                            let data = &mut *update_data.0.borrow_mut();
+                        Box::new(queue.run(move |token| {
+                            let data = &mut *update_data.borrow_mut(token);
                             data.is_playing = is_playing;
-                            if let Some(image) = image {
-                                data.texture = image;
+                            data.track = track;
+                            match image {
+                                Some(Some(image)) => {
+                                    data.has_art =
+                                        update_texture(&mut data.texture, &image, token)
+                                }
+                                Some(None) => data.has_art = false,
+                                None => {}
                             }
-                            Ok(())
-                        })) as Box<Future<Item = (), Error = ()>>
+                        }).map_err(|_| ())) as Box<Future<Item = (), Error = ()>>
                     });
                     *art_address = address.map(|a| a.to_string());
                     result
@@ -215,35 +260,65 @@ impl VideoSourceDefinition for AlbumArtSourceDefinition {
                 }
             }
         };
-        AlbumArtSource { client: art_client }
+        AlbumArtSource {
+            client: art_client,
+            autohide: Autohide::new(settings),
+            track: None,
+            width: 1,
+            height: 1,
+        }
+    }
+    fn get_defaults(&self, settings: &mut Data) {
+        autohide::get_defaults(settings);
     }
 }
 
 pub struct AlbumArtSource {
     client: Option<Arc<ArtClient>>,
+    autohide: Autohide,
+    // the last value of `ArtData::track` we saw
+    track: Option<u64>,
+    // copied from `ArtData` while rendering because OBS asks for the size
+    // from other threads
+    width: u32,
+    height: u32,
 }
 
 impl VideoSource for AlbumArtSource {
+    fn get_properties(&self) -> Properties {
+        let mut props = Properties::new();
+        autohide::add_properties(&mut props);
+        props
+    }
+    fn update(&mut self, settings: &Data) {
+        self.autohide.update(settings);
+    }
     fn get_width(&self) -> u32 {
-        // obs doesn't like 0x0 sources
-        self.client
-            .as_ref()
-            .and_then(|client| client.data.0.borrow().texture.as_ref().map(|t| t.width()))
-            .unwrap_or(1)
+        self.width
     }
     fn get_height(&self) -> u32 {
-        // obs doesn't like 0x0 sources
-        self.client
-            .as_ref()
-            .and_then(|client| client.data.0.borrow().texture.as_ref().map(|t| t.height()))
-            .unwrap_or(1)
+        self.height
+    }
+    fn video_tick(&mut self, seconds: f32) {
+        self.autohide.tick(seconds);
     }
-    fn video_render(&mut self) {
+    fn video_render(&mut self, token: &RenderToken) {
         if let Some(ref client) = self.client {
-            let data = client.data.0.borrow();
-            if data.is_playing {
-                if let Some(ref texture) = data.texture {
-                    texture.draw();
+            let data = client.data.borrow(token);
+            let track_changed = self.track.map(|t| t != data.track).unwrap_or(false);
+            self.track = Some(data.track);
+            self.autohide.set_state(data.is_playing, track_changed);
+            // obs doesn't like 0x0 sources
+            let texture = if data.has_art {
+                data.texture.as_ref()
+            } else {
+                None
+            };
+            self.width = texture.map(|t| t.width()).unwrap_or(1);
+            self.height = texture.map(|t| t.height()).unwrap_or(1);
+            if self.autohide.is_visible() {
+                if let Some(texture) = texture {
+                    texture.draw(token);
                 }
             }
         }
@@ -0,0 +1,133 @@
+use GpmdpTrack;
+use obs::{Data, Properties, Settings};
+
+#[derive(Settings)]
+struct AutohideSettings {
+    #[obs(label = "Hide After Not Playing (seconds)", default = 0.0, min = 0.0, max = 60.0,
+          step = 0.5)]
+    hide_delay: f64,
+    #[obs(label = "Show After Track Change For At Least (seconds)", default = 0.0, min = 0.0,
+          max = 60.0, step = 0.5)]
+    minimum_display: f64,
+}
+
+// set in the settings of sources shown by another source that hides them itself
+const DISABLED_KEY: &str = "autohide_disabled";
+
+// decides whether a source should be shown based on the playback state. short
+// pauses and quick track changes are smoothed over so the source doesn't
+// flicker.
+pub(super) struct Autohide {
+    // seconds to keep showing after playback stops
+    hide_delay: f32,
+    // seconds to show after a track change, even if playback stops
+    minimum_display: f32,
+    // always visible, see `disable`
+    disabled: bool,
+    is_playing: bool,
+    visible: bool,
+    stopped_for: f32,
+    shown_for: f32,
+}
+
+impl Autohide {
+    pub fn new(settings: &Data) -> Self {
+        let mut autohide = Autohide {
+            hide_delay: 0.0,
+            minimum_display: 0.0,
+            disabled: false,
+            is_playing: false,
+            visible: false,
+            stopped_for: 0.0,
+            shown_for: 0.0,
+        };
+        autohide.update(settings);
+        autohide
+    }
+
+    pub fn update(&mut self, settings: &Data) {
+        let settings = AutohideSettings::from_data(settings);
+        self.hide_delay = settings.hide_delay as f32;
+        self.minimum_display = settings.minimum_display as f32;
+        self.disabled = settings.get_bool(DISABLED_KEY);
+        if self.disabled {
+            self.visible = true;
+        }
+    }
+
+    // returns true if the visibility changed
+    pub fn set_state(&mut self, is_playing: bool, track_changed: bool) -> bool {
+        if self.is_playing && !is_playing {
+            self.stopped_for = 0.0;
+        }
+        self.is_playing = is_playing;
+        if track_changed {
+            self.shown_for = 0.0;
+        }
+        if !self.visible && (is_playing || (track_changed && self.minimum_display > 0.0)) {
+            self.visible = true;
+            self.shown_for = 0.0;
+            return true;
+        }
+        self.check_hide()
+    }
+
+    // returns true if the visibility changed
+    pub fn tick(&mut self, seconds: f32) -> bool {
+        self.shown_for += seconds;
+        if !self.is_playing {
+            self.stopped_for += seconds;
+        }
+        self.check_hide()
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn check_hide(&mut self) -> bool {
+        if self.visible && !self.disabled && !self.is_playing
+            && self.stopped_for >= self.hide_delay
+            && self.shown_for >= self.minimum_display
+        {
+            self.visible = false;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+// artist, album and title. all empty means there's no track.
+pub(super) type Track = (String, String, String);
+
+pub(super) fn to_track(track: Option<&GpmdpTrack>) -> Track {
+    match track {
+        Some(t) => (
+            t.artist.clone().unwrap_or_default(),
+            t.album.clone().unwrap_or_default(),
+            t.title.clone().unwrap_or_default(),
+        ),
+        None => Track::default(),
+    }
+}
+
+// whether going from `last` to `current` should count as a track change for
+// `Autohide::set_state`. losing the track isn't a change, and neither is new
+// album art for the same track.
+pub(super) fn track_changed(last: &Track, current: &Track) -> bool {
+    *current != Track::default() && current != last
+}
+
+// makes sources created with `settings` always visible
+pub(super) fn disable(settings: &mut Data) {
+    settings.set_bool(DISABLED_KEY, true);
+}
+
+pub(super) fn get_defaults(settings: &mut Data) {
+    AutohideSettings::set_defaults(settings);
+}
+
+pub(super) fn add_properties(props: &mut Properties) {
+    AutohideSettings::add_properties(props);
+}
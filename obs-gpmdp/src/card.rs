@@ -0,0 +1,338 @@
+use {Client, ClientAccess, ClientId};
+use autohide::{self, Autohide, Track};
+use obs::{self, Data, ObsSource, Properties, RenderToken, Settings, VideoSource,
+          VideoSourceDefinition};
+use std::sync::Arc;
+use text::{self, TrackSettings};
+
+// the key used in settings, and the label
+const FIELDS: [(&str, &str); 3] = [("title", "Title"), ("artist", "Artist"), ("album", "Album")];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Layout {
+    Vertical,
+    Horizontal,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Alignment {
+    Start,
+    Center,
+    End,
+}
+
+impl Layout {
+    fn from_settings(settings: &Data) -> Self {
+        match settings.get_string("layout").as_ref().map(|s| s.as_str()) {
+            Some("horizontal") => Layout::Horizontal,
+            _ => Layout::Vertical,
+        }
+    }
+}
+
+impl Alignment {
+    fn from_settings(settings: &Data) -> Self {
+        match settings.get_string("alignment").as_ref().map(|s| s.as_str()) {
+            Some("center") => Alignment::Center,
+            Some("end") => Alignment::End,
+            _ => Alignment::Start,
+        }
+    }
+}
+
+fn create_field_settings(settings: &Data, field: &str, text: &str) -> Data {
+    let mut child_settings = Data::new();
+    if let Some(font) = settings.get_obj(&format!("{}_font", field)) {
+        child_settings.set_obj("font", &font.deep_copy());
+    }
+    let color = settings.get_int(&format!("{}_color", field));
+    // text_gdiplus takes the alpha as a separate opacity percentage
+    child_settings.set_int("color", color & 0xFF_FFFF);
+    child_settings.set_int("opacity", ((color >> 24) & 0xFF) * 100 / 255);
+    // text_ft2_source draws a gradient between two colors
+    child_settings.set_int("color1", color);
+    child_settings.set_int("color2", color);
+    child_settings.set_string("text", text);
+    child_settings
+}
+
+// the card hides the art along with everything else, so the art shouldn't
+// hide itself
+fn create_art_settings() -> Data {
+    let mut art_settings = Data::new();
+    autohide::disable(&mut art_settings);
+    art_settings
+}
+
+pub(super) struct NowPlayingCardSourceDefinition {
+    client_access: Arc<ClientAccess>,
+}
+
+impl NowPlayingCardSourceDefinition {
+    pub fn new(client_access: &Arc<ClientAccess>) -> Self {
+        Self {
+            client_access: client_access.clone(),
+        }
+    }
+}
+
+pub(super) struct NowPlayingCardSource {
+    _client: Option<Client>,
+    // in the same order as FIELDS
+    fields: Vec<Option<ObsSource>>,
+    art: Option<ObsSource>,
+    layout: Layout,
+    alignment: Alignment,
+    spacing: u32,
+    show_art: bool,
+    // 0 to use the size of the image
+    art_size: u32,
+    autohide: Autohide,
+    track: Track,
+    // the settings for each field's child and the text to show while visible,
+    // in the same order as FIELDS. they're copied out of the settings in
+    // `update` because OBS changes those from other threads.
+    field_settings: Vec<(Data, String)>,
+}
+
+impl NowPlayingCardSource {
+    // takes what we need from `settings`, which can't be kept
+    fn read_settings(&mut self, settings: &Data) {
+        self.layout = Layout::from_settings(settings);
+        self.alignment = Alignment::from_settings(settings);
+        self.spacing = settings.get_int("spacing").max(0) as u32;
+        self.show_art = settings.get_bool("show_art");
+        self.art_size = settings.get_int("art_size").max(0) as u32;
+        self.field_settings = FIELDS
+            .iter()
+            .map(|&(field, _)| {
+                (
+                    create_field_settings(settings, field, ""),
+                    settings.get_string(field).unwrap_or_default(),
+                )
+            })
+            .collect();
+    }
+
+    // applies the last settings we were given to the children
+    fn refresh(&mut self) {
+        let visible = self.autohide.is_visible();
+        for (child, &mut (ref mut child_settings, ref text)) in
+            self.fields.iter().zip(self.field_settings.iter_mut())
+        {
+            if let Some(ref child) = *child {
+                child_settings.set_string("text", if visible { text.as_str() } else { "" });
+                child.update(child_settings);
+            }
+        }
+        if let Some(ref art) = self.art {
+            art.update(&create_art_settings());
+        }
+    }
+
+    // works out where each child goes. returns the total size and the
+    // children with their positions and scales.
+    fn arrange(&self) -> (u32, u32, Vec<(&ObsSource, f32, f32, f32)>) {
+        let mut items = Vec::new();
+        if self.show_art && self.autohide.is_visible() {
+            if let Some(ref art) = self.art {
+                let (width, height) = (art.get_width(), art.get_height());
+                // the art source is 1x1 when there's no art
+                if width > 1 && height > 1 {
+                    let scale = if self.art_size > 0 {
+                        self.art_size as f32 / width.max(height) as f32
+                    } else {
+                        1.0
+                    };
+                    items.push((art, width as f32 * scale, height as f32 * scale, scale));
+                }
+            }
+        }
+        for child in &self.fields {
+            if let Some(ref child) = *child {
+                let (width, height) = (child.get_width(), child.get_height());
+                if width > 0 && height > 0 {
+                    items.push((child, width as f32, height as f32, 1.0));
+                }
+            }
+        }
+
+        let spacing = self.spacing as f32;
+        let (mut main, mut cross) = (0.0f32, 0.0f32);
+        for &(_, width, height, _) in &items {
+            let (item_main, item_cross) = match self.layout {
+                Layout::Vertical => (height, width),
+                Layout::Horizontal => (width, height),
+            };
+            main += item_main;
+            cross = cross.max(item_cross);
+        }
+        main += spacing * items.len().saturating_sub(1) as f32;
+
+        let mut position = 0.0;
+        let placed = items
+            .into_iter()
+            .map(|(source, width, height, scale)| {
+                let (item_main, item_cross) = match self.layout {
+                    Layout::Vertical => (height, width),
+                    Layout::Horizontal => (width, height),
+                };
+                let offset = match self.alignment {
+                    Alignment::Start => 0.0,
+                    Alignment::Center => ((cross - item_cross) / 2.0).floor(),
+                    Alignment::End => cross - item_cross,
+                };
+                let (x, y) = match self.layout {
+                    Layout::Vertical => (offset, position),
+                    Layout::Horizontal => (position, offset),
+                };
+                position += item_main + spacing;
+                (source, x, y, scale)
+            })
+            .collect();
+
+        let (width, height) = match self.layout {
+            Layout::Vertical => (cross, main),
+            Layout::Horizontal => (main, cross),
+        };
+        (width.ceil() as u32, height.ceil() as u32, placed)
+    }
+}
+
+impl VideoSourceDefinition for NowPlayingCardSourceDefinition {
+    type Source = NowPlayingCardSource;
+    fn create(&self, settings: &Data, source: &mut ObsSource) -> Self::Source {
+        let track = TrackSettings::from_data(settings);
+        let mut autohide = Autohide::new(settings);
+        autohide.set_state(track.is_playing, false);
+        let fields = FIELDS
+            .iter()
+            .map(|&(field, _)| {
+                // always the default type, whose defaults get_defaults forwards
+                let child = obs::source_create_private(
+                    text::DEFAULT_TEXT_TYPE,
+                    Some(&format!("gpmdp-card-{}", field)),
+                    Some(&create_field_settings(settings, field, "")),
+                );
+                if child.is_none() {
+                    error!("failed to create text source for {}", field);
+                }
+                child
+            })
+            .collect();
+        let art = obs::source_create_private(
+            "gpmdp-album-art",
+            Some("gpmdp-card-art"),
+            Some(&create_art_settings()),
+        );
+        let mut card = NowPlayingCardSource {
+            _client: text::watch_track(
+                &self.client_access,
+                &ClientId::Card(source.get_name()),
+                source,
+            ),
+            fields,
+            art,
+            layout: Layout::Vertical,
+            alignment: Alignment::Start,
+            spacing: 0,
+            show_art: true,
+            art_size: 0,
+            autohide,
+            track: track.track(),
+            field_settings: Vec::new(),
+        };
+        card.read_settings(settings);
+        card.refresh();
+        card
+    }
+    fn get_defaults(&self, settings: &mut Data) {
+        let font = obs::get_source_defaults(text::DEFAULT_TEXT_TYPE)
+            .and_then(|defaults| defaults.get_obj("font"));
+        for &(field, _) in FIELDS.iter() {
+            let mut field_font = Data::new();
+            if let Some(ref font) = font {
+                field_font.apply_defaults(font);
+            }
+            field_font.set_int("size", if field == "title" { 48 } else { 32 });
+            settings.set_default_obj(&format!("{}_font", field), &field_font);
+            settings.set_default_int(&format!("{}_color", field), 0xFFFF_FFFF);
+        }
+        settings.set_default_string("layout", "vertical");
+        settings.set_default_string("alignment", "start");
+        settings.set_default_int("spacing", 8);
+        settings.set_default_bool("show_art", true);
+        settings.set_default_int("art_size", 128);
+        autohide::get_defaults(settings);
+    }
+}
+
+impl VideoSource for NowPlayingCardSource {
+    fn get_properties(&self) -> Properties {
+        let mut props = Properties::new();
+        {
+            let mut layout = props.add_string_list("layout", &::obs_module_text("Layout"));
+            layout.add_string_item(&::obs_module_text("Vertical"), "vertical");
+            layout.add_string_item(&::obs_module_text("Horizontal"), "horizontal");
+        }
+        {
+            let mut alignment =
+                props.add_string_list("alignment", &::obs_module_text("Alignment"));
+            alignment.add_string_item(&::obs_module_text("Left/Top"), "start");
+            alignment.add_string_item(&::obs_module_text("Center"), "center");
+            alignment.add_string_item(&::obs_module_text("Right/Bottom"), "end");
+        }
+        props.add_int("spacing", &::obs_module_text("Spacing"), 0, 1000, 1);
+        props.add_bool("show_art", &::obs_module_text("Show Album Art"));
+        props.add_int(
+            "art_size",
+            &::obs_module_text("Album Art Size (0 for original)"),
+            0,
+            4096,
+            1,
+        );
+        for &(field, label) in FIELDS.iter() {
+            props.add_font(
+                &format!("{}_font", field),
+                &::obs_module_text(&format!("{} Font", label)),
+            );
+            props.add_color(
+                &format!("{}_color", field),
+                &::obs_module_text(&format!("{} Color", label)),
+            );
+        }
+        autohide::add_properties(&mut props);
+        props
+    }
+    fn update(&mut self, settings: &Data) {
+        self.autohide.update(settings);
+        let track = TrackSettings::from_data(settings);
+        let track_changed = autohide::track_changed(&self.track, &track.track());
+        self.track = track.track();
+        self.autohide.set_state(track.is_playing, track_changed);
+        self.read_settings(settings);
+        self.refresh();
+    }
+    fn get_width(&self) -> u32 {
+        self.arrange().0
+    }
+    fn get_height(&self) -> u32 {
+        self.arrange().1
+    }
+    fn video_tick(&mut self, seconds: f32) {
+        if self.autohide.tick(seconds) {
+            self.refresh();
+        }
+    }
+    fn enum_active_sources(&mut self, enum_callback: &mut FnMut(&ObsSource)) {
+        for child in self.fields.iter().chain(Some(&self.art)).filter_map(|c| c.as_ref()) {
+            enum_callback(child);
+        }
+    }
+    fn video_render(&mut self, token: &RenderToken) {
+        let (_, _, placed) = self.arrange();
+        for (source, x, y, scale) in placed {
+            obs::with_transform(token, x, y, scale, || source.video_render(token));
+        }
+    }
+}
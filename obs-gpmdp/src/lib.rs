@@ -6,8 +6,11 @@ extern crate hyper;
 extern crate hyper_tls;
 extern crate image;
 #[macro_use]
-extern crate lazy_static;
-extern crate libobs_sys as libobs;
+extern crate log;
+#[macro_use]
+extern crate obs;
+#[macro_use]
+extern crate obs_derive;
 extern crate serde;
 #[macro_use]
 extern crate serde_derive;
@@ -16,12 +19,13 @@ extern crate tera;
 extern crate tokio_core;
 extern crate websocket;
 
-mod macros;
-mod obs;
 mod art;
+mod autohide;
+mod card;
 mod text;
 
 use art::AlbumArtSourceDefinition;
+use card::NowPlayingCardSourceDefinition;
 use futures::{future, stream, Future, IntoFuture, Stream};
 use futures::sync::oneshot;
 use std::collections::BTreeMap;
@@ -43,7 +47,7 @@ obs_declare_module!(
 
 obs_module_use_default_locale!("en-US");
 
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq)]
 struct GpmdpTrack {
     artist: Option<String>,
     album: Option<String>,
@@ -60,6 +64,7 @@ struct GpmdpState {
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
 enum ClientId {
     Text(String),
+    Card(String),
     Art,
 }
 
@@ -67,6 +72,7 @@ impl ClientId {
     pub fn to_owned(&self) -> ClientId {
         match *self {
             ClientId::Text(ref text) => ClientId::Text(text.to_string()),
+            ClientId::Card(ref card) => ClientId::Card(card.to_string()),
             ClientId::Art => ClientId::Art,
         }
     }
@@ -207,6 +213,17 @@ impl ClientAccess {
     }
 }
 
+// lets scripts and other plugins find out about new tracks
+const TRACK_CHANGED_DECL: &str = "void track_changed(string title, string artist, string album)";
+
+fn signal_track_changed(track: &GpmdpTrack) {
+    let mut data = obs::CallData::new();
+    data.set_string("title", track.title.as_ref().map_or("", |s| s.as_str()));
+    data.set_string("artist", track.artist.as_ref().map_or("", |s| s.as_str()));
+    data.set_string("album", track.album.as_ref().map_or("", |s| s.as_str()));
+    obs::get_signal_handler().signal("track_changed", &mut data);
+}
+
 struct GpmdpModule {}
 
 impl obs::Module<GpmdpModule> for GpmdpModule {
@@ -214,6 +231,9 @@ impl obs::Module<GpmdpModule> for GpmdpModule {
         let client_access = Arc::new(ClientAccess {
             client: Mutex::new((Weak::default(), Weak::default())),
         });
+        if !obs::get_signal_handler().add_signal(TRACK_CHANGED_DECL) {
+            warn!("failed to declare the track_changed signal");
+        }
         obs::register_source(
             "gpmdp-album-art",
             &obs_module_text("GPMDP Album Art"),
@@ -224,6 +244,11 @@ impl obs::Module<GpmdpModule> for GpmdpModule {
             &obs_module_text("GPMDP Now Playing"),
             NowPlayingSourceDefinition::new(&client_access),
         );
+        obs::register_source(
+            "gpmdp-now-playing-card",
+            &obs_module_text("GPMDP Now Playing Card"),
+            NowPlayingCardSourceDefinition::new(&client_access),
+        );
         Some(Self {})
     }
 }
@@ -337,13 +362,28 @@ fn read_events(
                         }
                         Ok(Message::Track(track)) => {
                             info!("got track data: {:?}", track);
-                            let mut guard = client_state.lock().unwrap();
-                            guard.current_state.track = Some(GpmdpTrack {
+                            let track = GpmdpTrack {
                                 artist: track.artist,
                                 album: track.album,
                                 title: track.title,
                                 album_art: track.album_art,
-                            });
+                            };
+                            let changed = {
+                                let mut guard = client_state.lock().unwrap();
+                                // new album art for the same track isn't a change
+                                let changed = autohide::track_changed(
+                                    &autohide::to_track(guard.current_state.track.as_ref()),
+                                    &autohide::to_track(Some(&track)),
+                                );
+                                guard.current_state.track = Some(track.clone());
+                                changed
+                            };
+                            // whatever is connected to the signal may want the
+                            // client state, so it's sent without the lock held
+                            if changed {
+                                signal_track_changed(&track);
+                            }
+                            let guard = client_state.lock().unwrap();
                             Box::new(
                                 stream::futures_unordered(guard.handlers.values().map(|h| {
                                     h(&guard.current_state, &update_handle)
@@ -1,44 +1,218 @@
 use {Client, ClientAccess, ClientId};
+use autohide::{self, Autohide, Track};
 use futures::future;
-use obs::{self, Data, ObsSource, Properties, VideoSource, VideoSourceDefinition};
-use std::borrow::Cow;
+use obs::{self, Data, Effect, ObsSource, Properties, RenderToken, Settings, SourceType,
+          TextureRender, VideoSource, VideoSourceDefinition};
 use std::sync::Arc;
 use tera::{self, Tera};
 
 #[cfg(windows)]
-const TEXT_TYPE: &str = "text_gdiplus";
+pub(super) const DEFAULT_TEXT_TYPE: &str = "text_gdiplus";
 #[cfg(not(windows))]
-const TEXT_TYPE: &str = "text_ft2_source";
-
-fn create_child_settings(settings: &Data) -> Data {
-    let is_playing = settings.get_bool("is_playing");
-
-    let text = if is_playing {
-        let artist = settings.get_string("artist");
-        let album = settings.get_string("album");
-        let title = settings.get_string("title");
-        let template = settings.get_string("text");
-        let template = template.as_ref().map(|s| s.as_str()).unwrap_or("");
-
-        let mut context = tera::Context::new();
-        context.add("artist", &artist);
-        context.add("album", &album);
-        context.add("title", &title);
-        Cow::Owned(
-            Tera::one_off(template, &context, false).unwrap_or_else(|e| format!("error: {:?}", e)),
-        )
-    } else {
-        Cow::Borrowed("")
-    };
-
-    let mut child_settings = Data::new();
-    child_settings.apply(settings);
-    child_settings.set_string("text", &text);
+pub(super) const DEFAULT_TEXT_TYPE: &str = "text_ft2_source";
+
+// the default effect has no way to fade, so we need our own
+const TRANSITION_EFFECT: &str = r#"
+uniform float4x4 ViewProj;
+uniform texture2d image;
+uniform float opacity;
+
+sampler_state def_sampler {
+    Filter   = Linear;
+    AddressU = Clamp;
+    AddressV = Clamp;
+};
+
+struct VertInOut {
+    float4 pos : POSITION;
+    float2 uv  : TEXCOORD0;
+};
+
+VertInOut VSDefault(VertInOut vert_in)
+{
+    VertInOut vert_out;
+    vert_out.pos = mul(float4(vert_in.pos.xyz, 1.0), ViewProj);
+    vert_out.uv  = vert_in.uv;
+    return vert_out;
+}
+
+float4 PSOpacity(VertInOut vert_in) : TARGET
+{
+    float4 rgba = image.Sample(def_sampler, vert_in.uv);
+    rgba.a *= opacity;
+    return rgba;
+}
+
+technique Draw
+{
+    pass
+    {
+        vertex_shader = VSDefault(vert_in);
+        pixel_shader  = PSOpacity(vert_in);
+    }
+}
+"#;
+
+#[derive(Clone, Copy, Debug, PartialEq, SettingsValue)]
+enum Transition {
+    None,
+    Fade,
+    Slide,
+}
+
+impl Default for Transition {
+    fn default() -> Self {
+        Transition::None
+    }
+}
+
+#[derive(Settings)]
+struct TransitionSettings {
+    #[obs(label = "Transition", default)]
+    transition: Transition,
+    // milliseconds
+    #[obs(label = "Transition Duration (ms)", default = 500, min = 0, max = 5000, step = 50)]
+    transition_duration: i64,
+}
+
+#[derive(Settings)]
+struct TemplateSettings {
+    #[obs(key = "text", default = "{{title}}\n{{artist}} - {{album}}")]
+    template: String,
+}
+
+// what `watch_track` keeps in the settings of a source. empty strings mean
+// there's no track.
+#[derive(Settings)]
+pub(super) struct TrackSettings {
+    #[obs(default = "[artist]")]
+    pub artist: String,
+    #[obs(default = "[album]")]
+    pub album: String,
+    #[obs(default = "[title]")]
+    pub title: String,
+    pub is_playing: bool,
+}
+
+impl TrackSettings {
+    pub fn track(&self) -> Track {
+        (self.artist.clone(), self.album.clone(), self.title.clone())
+    }
+}
+
+// keeps the track information in the settings of `source` up to date
+pub(super) fn watch_track(
+    client_access: &ClientAccess,
+    id: &ClientId,
+    source: &ObsSource,
+) -> Option<Client> {
+    let update_source = source.get_weak_source();
+    client_access
+        .client(id, move |s, _| {
+            if let Some(source) = update_source.upgrade() {
+                let (artist, album, title) = autohide::to_track(s.track.as_ref());
+                let mut data = Data::new();
+                TrackSettings {
+                    artist,
+                    album,
+                    title,
+                    is_playing: s.is_playing,
+                }.to_data(&mut data);
+                source.update(&data);
+            }
+            future::ok(())
+        })
+        .map_err(|e| error!("failed to get client: {:?}", e))
+        .ok()
+}
+
+fn render_text(settings: &Data) -> String {
+    let track = TrackSettings::from_data(settings);
+    let template = TemplateSettings::from_data(settings).template;
+
+    let mut context = tera::Context::new();
+    context.add("artist", &track.artist);
+    context.add("album", &track.album);
+    context.add("title", &track.title);
+    Tera::one_off(&template, &context, false).unwrap_or_else(|e| format!("error: {:?}", e))
+}
+
+fn get_text_type(settings: &Data) -> String {
+    settings
+        .get_string("text_type")
+        .and_then(|t| if t.is_empty() { None } else { Some(t) })
+        .unwrap_or_else(|| DEFAULT_TEXT_TYPE.to_string())
+}
+
+// a copy of `settings` for children of `text_type`. `get_defaults` doesn't
+// know which text type a source will use, so `settings` only has the defaults
+// of DEFAULT_TEXT_TYPE. the copy gets the defaults of `text_type` instead,
+// with only what the user set on top.
+fn copy_for_children(text_type: &str, settings: &Data) -> Data {
+    let mut copy = Data::new();
+    match obs::get_source_defaults(text_type) {
+        Some(defaults) => copy.apply_defaults(&defaults),
+        None => warn!("no defaults for text source type {:?}", text_type),
+    }
+    copy.apply(settings);
+    copy
+}
+
+// there's no way to ask OBS which sources display text, so we take any
+// synchronous video source with a property named "text"
+fn is_text_type(source_type: &SourceType) -> bool {
+    let flags = source_type.get_output_flags();
+    flags & obs::OBS_SOURCE_VIDEO != 0 && flags & obs::OBS_SOURCE_ASYNC == 0
+        && source_type
+            .get_properties()
+            .map(|mut p| p.get_property("text").is_some())
+            .unwrap_or(false)
+}
+
+fn create_children(text_type: &str, settings: &Data, text: &str) -> [Option<ObsSource>; 2] {
+    let children = [
+        obs::source_create_private(
+            text_type,
+            Some("gpmdp-text"),
+            Some(&create_child_settings(settings, text)),
+        ),
+        obs::source_create_private(
+            text_type,
+            Some("gpmdp-text"),
+            Some(&create_child_settings(settings, "")),
+        ),
+    ];
+    if children[0].is_none() || children[1].is_none() {
+        error!("failed to create text source of type {:?}", text_type);
+    }
+    children
+}
+
+fn create_child_settings(settings: &Data, text: &str) -> Data {
+    let mut child_settings = settings.deep_copy();
+    child_settings.set_string("text", text);
     child_settings.set_bool("read_from_file", false);
     child_settings.set_bool("chatlog", false);
     child_settings
 }
 
+fn render_child(
+    child: &Option<ObsSource>,
+    render: &mut TextureRender,
+    effect: &mut Effect,
+    (width, height): (u32, u32),
+    offset: f32,
+    opacity: f32,
+    token: &RenderToken,
+) {
+    if let Some(ref child) = *child {
+        if render.render(token, width, height, 0.0, offset, || child.video_render(token)) {
+            effect.set_float("opacity", opacity, token);
+            effect.draw_sprite("Draw", render, width, height, token);
+        }
+    }
+}
+
 pub(super) struct NowPlayingSourceDefinition {
     client_access: Arc<ClientAccess>,
 }
@@ -53,71 +227,113 @@ impl NowPlayingSourceDefinition {
 
 pub(super) struct NowPlayingSource {
     _client: Option<Client>,
-    text: Option<ObsSource>,
+    // `texts[front]` is the child being shown. during a transition the other
+    // child holds the text being transitioned away from.
+    texts: [Option<ObsSource>; 2],
+    text_type: String,
+    front: usize,
+    text: String,
+    transition: Transition,
+    // seconds
+    duration: f32,
+    // 1.0 when there is no transition running
+    progress: f32,
+    renders: Option<(TextureRender, TextureRender)>,
+    effect: Option<Effect>,
+    autohide: Autohide,
+    track: Track,
+    // the text to show while visible, rendered from the last settings
+    rendered: String,
+    // a copy of the last settings we were given, for the children. OBS
+    // changes the settings it gives us from other threads, like when
+    // `watch_track` updates the track, so they can't be kept and read later.
+    settings: Data,
+}
+
+impl NowPlayingSource {
+    fn is_transitioning(&self) -> bool {
+        self.progress < 1.0
+    }
+
+    // passes the text from the last settings we were given to the children
+    fn refresh(&mut self) {
+        let text = if self.autohide.is_visible() {
+            self.rendered.clone()
+        } else {
+            String::new()
+        };
+        let child_settings = create_child_settings(&self.settings, &text);
+        if text != self.text && self.transition != Transition::None && self.duration > 0.0 {
+            // put the new text on the back child and bring it to the front
+            let back = 1 - self.front;
+            if let Some(ref child) = self.texts[back] {
+                child.update(&child_settings);
+            }
+            self.front = back;
+            self.progress = 0.0;
+        } else {
+            if let Some(ref child) = self.texts[self.front] {
+                child.update(&child_settings);
+            }
+            if text != self.text {
+                self.progress = 1.0;
+            }
+        }
+        self.text = text;
+    }
 }
 
 impl VideoSourceDefinition for NowPlayingSourceDefinition {
     type Source = NowPlayingSource;
     fn create(&self, settings: &Data, source: &mut ObsSource) -> Self::Source {
-        let update_source = source.get_weak_source();
+        let text_type = get_text_type(settings);
+        let child_settings = copy_for_children(&text_type, settings);
+        let track = TrackSettings::from_data(settings);
+        let mut autohide = Autohide::new(settings);
+        autohide.set_state(track.is_playing, false);
+        let rendered = render_text(settings);
+        let text = if autohide.is_visible() {
+            rendered.clone()
+        } else {
+            String::new()
+        };
+        let transition = TransitionSettings::from_data(settings);
         NowPlayingSource {
-            _client: self.client_access
-                .client(&ClientId::Text(source.get_name()), move |s, _| {
-                    if let Some(source) = update_source.upgrade() {
-                        let mut data = Data::new();
-                        data.set_string(
-                            "artist",
-                            s.track
-                                .as_ref()
-                                .and_then(|s| s.artist.as_ref())
-                                .map(|s| s.as_str())
-                                .unwrap_or(""),
-                        );
-                        data.set_string(
-                            "album",
-                            s.track
-                                .as_ref()
-                                .and_then(|s| s.album.as_ref())
-                                .map(|s| s.as_str())
-                                .unwrap_or(""),
-                        );
-                        data.set_string(
-                            "title",
-                            s.track
-                                .as_ref()
-                                .and_then(|s| s.title.as_ref())
-                                .map(|s| s.as_str())
-                                .unwrap_or(""),
-                        );
-                        data.set_bool("is_playing", s.is_playing);
-                        source.update(&data);
-                    }
-                    future::ok(())
-                })
-                .map_err(|e| error!("failed to get client: {:?}", e))
-                .ok(),
-            text: obs::source_create_private(
-                TEXT_TYPE,
-                Some("gpmdp-text"),
-                Some(&create_child_settings(settings)),
+            _client: watch_track(
+                &self.client_access,
+                &ClientId::Text(source.get_name()),
+                source,
             ),
+            texts: create_children(&text_type, &child_settings, &text),
+            text_type,
+            front: 0,
+            text,
+            transition: transition.transition,
+            duration: transition.transition_duration as f32 / 1000.0,
+            progress: 1.0,
+            renders: None,
+            effect: None,
+            autohide,
+            track: track.track(),
+            rendered,
+            settings: child_settings,
         }
     }
     fn get_defaults(&self, settings: &mut Data) {
-        if let Some(text_settings) = obs::get_source_defaults(TEXT_TYPE) {
-            // this does not seem to work
-            settings.apply(&text_settings);
+        if let Some(text_settings) = obs::get_source_defaults(DEFAULT_TEXT_TYPE) {
+            settings.apply_defaults(&text_settings);
         }
-        settings.set_default_string("text", "{{title}}\n{{artist}} - {{album}}");
-        settings.set_default_string("artist", "[artist]");
-        settings.set_default_string("album", "[album]");
-        settings.set_default_string("title", "[title]");
+        settings.set_default_string("text_type", DEFAULT_TEXT_TYPE);
+        TemplateSettings::set_defaults(settings);
+        TrackSettings::set_defaults(settings);
+        TransitionSettings::set_defaults(settings);
+        autohide::get_defaults(settings);
     }
 }
 
 impl VideoSource for NowPlayingSource {
     fn get_properties(&self) -> Properties {
-        let props = match self.text {
+        let mut props = match self.texts[self.front] {
             Some(ref text) => text.get_properties(),
             None => Properties::new(),
         };
@@ -130,28 +346,128 @@ impl VideoSource for NowPlayingSource {
         if let Some(mut chatlog) = props.get_property("chatlog") {
             chatlog.set_visible(false);
         }
+        {
+            let mut text_type =
+                props.add_string_list("text_type", &::obs_module_text("Text Renderer"));
+            for source_type in obs::enum_input_types() {
+                if is_text_type(&source_type) {
+                    let name = source_type
+                        .get_display_name()
+                        .unwrap_or_else(|| source_type.id().to_string());
+                    text_type.add_string_item(&name, source_type.id());
+                }
+            }
+            // the rest of the properties come from the renderer
+            text_type.set_refresh_on_change();
+        }
+        TransitionSettings::add_properties(&mut props);
+        autohide::add_properties(&mut props);
         props
     }
     fn update(&mut self, settings: &Data) {
-        if let Some(ref mut text) = self.text {
-            text.update(&create_child_settings(settings));
+        self.autohide.update(settings);
+        let track = TrackSettings::from_data(settings);
+        let track_changed = autohide::track_changed(&self.track, &track.track());
+        self.track = track.track();
+        self.autohide.set_state(track.is_playing, track_changed);
+        let transition = TransitionSettings::from_data(settings);
+        self.transition = transition.transition;
+        self.duration = transition.transition_duration as f32 / 1000.0;
+        self.rendered = render_text(settings);
+        let text_type = get_text_type(settings);
+        self.settings = copy_for_children(&text_type, settings);
+        if text_type != self.text_type {
+            // start over with fresh children of the new type
+            self.texts = create_children(&text_type, &self.settings, "");
+            self.text_type = text_type;
+            self.text = String::new();
+            self.progress = 1.0;
         }
+        self.refresh();
     }
     fn get_width(&self) -> u32 {
-        match self.text {
-            Some(ref text) => text.get_width(),
-            None => 0,
-        }
+        let front = self.texts[self.front].as_ref().map(|t| t.get_width());
+        let back = if self.is_transitioning() {
+            self.texts[1 - self.front].as_ref().map(|t| t.get_width())
+        } else {
+            None
+        };
+        front.unwrap_or(0).max(back.unwrap_or(0))
     }
     fn get_height(&self) -> u32 {
-        match self.text {
-            Some(ref text) => text.get_height(),
-            None => 0,
+        let front = self.texts[self.front].as_ref().map(|t| t.get_height());
+        let back = if self.is_transitioning() {
+            self.texts[1 - self.front].as_ref().map(|t| t.get_height())
+        } else {
+            None
+        };
+        front.unwrap_or(0).max(back.unwrap_or(0))
+    }
+    fn video_tick(&mut self, seconds: f32) {
+        if self.autohide.tick(seconds) {
+            self.refresh();
+        }
+        if self.is_transitioning() {
+            self.progress = (self.progress + seconds / self.duration).min(1.0);
+        }
+    }
+    fn enum_active_sources(&mut self, enum_callback: &mut FnMut(&ObsSource)) {
+        for text in self.texts.iter().filter_map(|t| t.as_ref()) {
+            enum_callback(text);
         }
     }
-    fn video_render(&mut self) {
-        if let Some(ref text) = self.text {
-            text.video_render();
+    fn video_render(&mut self, token: &RenderToken) {
+        if !self.is_transitioning() {
+            if let Some(ref text) = self.texts[self.front] {
+                text.video_render(token);
+            }
+            return;
+        }
+
+        let size = (self.get_width(), self.get_height());
+        if size.0 == 0 || size.1 == 0 {
+            return;
+        }
+        if self.effect.is_none() {
+            match Effect::from_string(TRANSITION_EFFECT, "gpmdp-transition", token) {
+                Ok(effect) => self.effect = Some(effect),
+                Err(e) => {
+                    error!("failed to create transition effect: {}", e);
+                    self.progress = 1.0;
+                    return;
+                }
+            }
         }
+        if self.renders.is_none() {
+            self.renders = Some((TextureRender::new(token), TextureRender::new(token)));
+        }
+
+        // ease in and out so the motion starts and stops gently
+        let t = self.progress * self.progress * (3.0 - 2.0 * self.progress);
+        let (outgoing_offset, incoming_offset, outgoing_opacity, incoming_opacity) =
+            match self.transition {
+                Transition::Slide => (-t * size.1 as f32, (1.0 - t) * size.1 as f32, 1.0, 1.0),
+                _ => (0.0, 0.0, 1.0 - t, t),
+            };
+        let effect = self.effect.as_mut().unwrap();
+        let renders = self.renders.as_mut().unwrap();
+        render_child(
+            &self.texts[1 - self.front],
+            &mut renders.0,
+            effect,
+            size,
+            outgoing_offset,
+            outgoing_opacity,
+            token,
+        );
+        render_child(
+            &self.texts[self.front],
+            &mut renders.1,
+            effect,
+            size,
+            incoming_offset,
+            incoming_opacity,
+            token,
+        );
     }
 }
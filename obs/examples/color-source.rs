@@ -0,0 +1,90 @@
+// a plugin with one source that fills its area with a color. build it with
+// `cargo build -p obs --example color-source` and copy the library from
+// target/debug/examples into the OBS plugins directory.
+
+#[macro_use]
+extern crate obs;
+#[macro_use]
+extern crate obs_derive;
+
+use obs::{Data, ObsSource, Properties, RenderToken, Settings, VideoSource,
+          VideoSourceDefinition};
+
+obs_declare_module!(
+    ColorModule,
+    "color-source",
+    "A source that is a single color."
+);
+
+obs_module_use_default_locale!("en-US");
+
+#[derive(Settings)]
+struct ColorSettings {
+    #[obs(label = "Width", default = 640, min = 1, max = 4096)]
+    width: u32,
+    #[obs(label = "Height", default = 360, min = 1, max = 4096)]
+    height: u32,
+    #[obs(label = "Red", default = 1.0, min = 0.0, max = 1.0, step = 0.01, slider)]
+    red: f32,
+    #[obs(label = "Green", default = 0.5, min = 0.0, max = 1.0, step = 0.01, slider)]
+    green: f32,
+    #[obs(label = "Blue", default = 0.0, min = 0.0, max = 1.0, step = 0.01, slider)]
+    blue: f32,
+}
+
+struct ColorSourceDefinition;
+
+impl VideoSourceDefinition for ColorSourceDefinition {
+    type Source = ColorSource;
+    fn create(&self, settings: &Data, _source: &mut ObsSource) -> ColorSource {
+        ColorSource {
+            settings: ColorSettings::from_data(settings),
+        }
+    }
+    fn get_defaults(&self, settings: &mut Data) {
+        ColorSettings::set_defaults(settings);
+    }
+}
+
+struct ColorSource {
+    settings: ColorSettings,
+}
+
+impl VideoSource for ColorSource {
+    fn update(&mut self, settings: &Data) {
+        self.settings = ColorSettings::from_data(settings);
+    }
+    fn get_width(&self) -> u32 {
+        self.settings.width
+    }
+    fn get_height(&self) -> u32 {
+        self.settings.height
+    }
+    fn get_properties(&self) -> Properties {
+        ColorSettings::properties()
+    }
+    fn video_render(&mut self, token: &RenderToken) {
+        let settings = &self.settings;
+        obs::draw_rect(
+            token,
+            [settings.red, settings.green, settings.blue, 1.0],
+            0.0,
+            0.0,
+            settings.width,
+            settings.height,
+        );
+    }
+}
+
+struct ColorModule;
+
+impl obs::Module<ColorModule> for ColorModule {
+    fn load() -> Option<Self> {
+        obs::register_source(
+            "rust-color-source",
+            &obs_module_text("Color"),
+            ColorSourceDefinition,
+        );
+        Some(ColorModule)
+    }
+}
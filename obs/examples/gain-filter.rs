@@ -0,0 +1,76 @@
+// a plugin with one audio filter that makes its source louder or quieter.
+// build it with `cargo build -p obs --example gain-filter` and copy the
+// library from target/debug/examples into the OBS plugins directory.
+
+#[macro_use]
+extern crate obs;
+#[macro_use]
+extern crate obs_derive;
+
+use obs::{Data, FilterAudio, FilterSource, FilterSourceDefinition, ObsFilter, Properties,
+          Settings};
+
+obs_declare_module!(
+    GainModule,
+    "gain-filter",
+    "A filter that changes the volume of a source."
+);
+
+obs_module_use_default_locale!("en-US");
+
+#[derive(Settings)]
+struct GainSettings {
+    #[obs(label = "Volume (%)", default = 100.0, min = 0.0, max = 400.0, step = 1.0, slider)]
+    volume: f32,
+}
+
+struct GainFilterDefinition;
+
+impl FilterSourceDefinition for GainFilterDefinition {
+    type Source = GainFilter;
+    fn create(&self, settings: &Data, _filter: &ObsFilter) -> GainFilter {
+        let mut filter = GainFilter { multiplier: 1.0 };
+        filter.update(settings);
+        filter
+    }
+    fn get_defaults(&self, settings: &mut Data) {
+        GainSettings::set_defaults(settings);
+    }
+    fn get_output_flags(&self) -> u32 {
+        obs::OBS_SOURCE_AUDIO
+    }
+}
+
+struct GainFilter {
+    multiplier: f32,
+}
+
+impl FilterSource for GainFilter {
+    fn update(&mut self, settings: &Data) {
+        self.multiplier = GainSettings::from_data(settings).volume / 100.0;
+    }
+    fn get_properties(&self) -> Properties {
+        GainSettings::properties()
+    }
+    fn filter_audio(&mut self, _filter: &ObsFilter, audio: &mut FilterAudio) -> bool {
+        for channel in audio.channels_mut() {
+            for sample in channel.iter_mut() {
+                *sample *= self.multiplier;
+            }
+        }
+        true
+    }
+}
+
+struct GainModule;
+
+impl obs::Module<GainModule> for GainModule {
+    fn load() -> Option<Self> {
+        obs::register_filter(
+            "rust-gain-filter",
+            &obs_module_text("Gain"),
+            GainFilterDefinition,
+        );
+        Some(GainModule)
+    }
+}
@@ -0,0 +1,208 @@
+use std::{mem, ptr, slice};
+use libobs;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AudioFormat {
+    U8,
+    S16,
+    S32,
+    Float,
+    U8Planar,
+    S16Planar,
+    S32Planar,
+    FloatPlanar,
+}
+
+impl AudioFormat {
+    pub fn bytes_per_sample(self) -> usize {
+        match self {
+            AudioFormat::U8 | AudioFormat::U8Planar => 1,
+            AudioFormat::S16 | AudioFormat::S16Planar => 2,
+            AudioFormat::S32
+            | AudioFormat::S32Planar
+            | AudioFormat::Float
+            | AudioFormat::FloatPlanar => 4,
+        }
+    }
+
+    // planar formats have one plane per channel, the others interleave all
+    // of the channels in a single plane
+    pub fn is_planar(self) -> bool {
+        match self {
+            AudioFormat::U8Planar
+            | AudioFormat::S16Planar
+            | AudioFormat::S32Planar
+            | AudioFormat::FloatPlanar => true,
+            _ => false,
+        }
+    }
+
+    fn as_raw(self) -> libobs::audio_format {
+        match self {
+            AudioFormat::U8 => libobs::audio_format_AUDIO_FORMAT_U8BIT,
+            AudioFormat::S16 => libobs::audio_format_AUDIO_FORMAT_16BIT,
+            AudioFormat::S32 => libobs::audio_format_AUDIO_FORMAT_32BIT,
+            AudioFormat::Float => libobs::audio_format_AUDIO_FORMAT_FLOAT,
+            AudioFormat::U8Planar => libobs::audio_format_AUDIO_FORMAT_U8BIT_PLANAR,
+            AudioFormat::S16Planar => libobs::audio_format_AUDIO_FORMAT_16BIT_PLANAR,
+            AudioFormat::S32Planar => libobs::audio_format_AUDIO_FORMAT_32BIT_PLANAR,
+            AudioFormat::FloatPlanar => libobs::audio_format_AUDIO_FORMAT_FLOAT_PLANAR,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SpeakerLayout {
+    Mono,
+    Stereo,
+    TwoPointOne,
+    FourPointOne,
+    FivePointOne,
+    SevenPointOne,
+}
+
+impl SpeakerLayout {
+    pub fn channels(self) -> usize {
+        match self {
+            SpeakerLayout::Mono => 1,
+            SpeakerLayout::Stereo => 2,
+            SpeakerLayout::TwoPointOne => 3,
+            SpeakerLayout::FourPointOne => 5,
+            SpeakerLayout::FivePointOne => 6,
+            SpeakerLayout::SevenPointOne => 8,
+        }
+    }
+
+    fn as_raw(self) -> libobs::speaker_layout {
+        match self {
+            SpeakerLayout::Mono => libobs::speaker_layout_SPEAKERS_MONO,
+            SpeakerLayout::Stereo => libobs::speaker_layout_SPEAKERS_STEREO,
+            SpeakerLayout::TwoPointOne => libobs::speaker_layout_SPEAKERS_2POINT1,
+            SpeakerLayout::FourPointOne => libobs::speaker_layout_SPEAKERS_4POINT1,
+            SpeakerLayout::FivePointOne => libobs::speaker_layout_SPEAKERS_5POINT1,
+            SpeakerLayout::SevenPointOne => libobs::speaker_layout_SPEAKERS_7POINT1,
+        }
+    }
+
+    fn from_raw(raw: libobs::speaker_layout) -> Option<Self> {
+        match raw {
+            libobs::speaker_layout_SPEAKERS_MONO => Some(SpeakerLayout::Mono),
+            libobs::speaker_layout_SPEAKERS_STEREO => Some(SpeakerLayout::Stereo),
+            libobs::speaker_layout_SPEAKERS_2POINT1 => Some(SpeakerLayout::TwoPointOne),
+            libobs::speaker_layout_SPEAKERS_4POINT1 => Some(SpeakerLayout::FourPointOne),
+            libobs::speaker_layout_SPEAKERS_5POINT1 => Some(SpeakerLayout::FivePointOne),
+            libobs::speaker_layout_SPEAKERS_7POINT1 => Some(SpeakerLayout::SevenPointOne),
+            _ => None,
+        }
+    }
+}
+
+// the sample rate and speaker layout OBS is mixing at
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AudioInfo {
+    pub samples_per_sec: u32,
+    pub speakers: Option<SpeakerLayout>,
+}
+
+pub fn get_audio_info() -> Option<AudioInfo> {
+    unsafe {
+        let mut info: libobs::obs_audio_info = mem::zeroed();
+        if !libobs::obs_get_audio_info(&mut info) {
+            return None;
+        }
+        Some(AudioInfo {
+            samples_per_sec: info.samples_per_sec,
+            speakers: SpeakerLayout::from_raw(info.speakers),
+        })
+    }
+}
+
+// the clock OBS uses for audio and video timestamps
+pub fn get_time_ns() -> u64 {
+    unsafe { libobs::os_gettime_ns() }
+}
+
+pub struct AudioFrame<'a> {
+    // one plane per channel for planar formats, otherwise a single plane
+    pub planes: &'a [&'a [u8]],
+    // the number of samples in each channel
+    pub frames: u32,
+    pub speakers: SpeakerLayout,
+    pub format: AudioFormat,
+    pub samples_per_sec: u32,
+    // in nanoseconds, see `get_time_ns`
+    pub timestamp: u64,
+}
+
+impl<'a> AudioFrame<'a> {
+    // OBS reads `frames` worth of samples from each plane without knowing
+    // how big they are, so check that they're all there first
+    pub(super) fn as_raw(&self) -> Option<libobs::obs_source_audio> {
+        let channels = self.speakers.channels();
+        let (plane_count, plane_size) = if self.format.is_planar() {
+            (channels, self.format.bytes_per_sample())
+        } else {
+            (1, self.format.bytes_per_sample() * channels)
+        };
+        let plane_size = plane_size * self.frames as usize;
+        if self.planes.len() != plane_count || plane_count > libobs::MAX_AV_PLANES as usize {
+            return None;
+        }
+        if self.planes.iter().any(|plane| plane.len() < plane_size) {
+            return None;
+        }
+
+        let mut data = [ptr::null(); libobs::MAX_AV_PLANES as usize];
+        for (target, plane) in data.iter_mut().zip(self.planes.iter()) {
+            *target = plane.as_ptr();
+        }
+        Some(libobs::obs_source_audio {
+            data,
+            frames: self.frames,
+            speakers: self.speakers.as_raw(),
+            format: self.format.as_raw(),
+            samples_per_sec: self.samples_per_sec,
+            timestamp: self.timestamp,
+        })
+    }
+}
+
+// audio passing through a filter. filters always get float planar samples at
+// the rate and speaker layout OBS is mixing at.
+pub struct FilterAudio<'a> {
+    audio: &'a mut libobs::obs_audio_data,
+    channels: usize,
+}
+
+impl<'a> FilterAudio<'a> {
+    pub(super) unsafe fn from_raw(audio: *mut libobs::obs_audio_data) -> Self {
+        let channels = get_audio_info()
+            .and_then(|info| info.speakers)
+            .map(|speakers| speakers.channels())
+            .unwrap_or(0);
+        FilterAudio {
+            audio: &mut *audio,
+            channels,
+        }
+    }
+
+    pub fn frames(&self) -> u32 {
+        self.audio.frames
+    }
+
+    pub fn timestamp(&self) -> u64 {
+        self.audio.timestamp
+    }
+
+    // one slice of `frames` samples per channel
+    pub fn channels_mut(&mut self) -> Vec<&mut [f32]> {
+        let frames = self.audio.frames as usize;
+        self.audio
+            .data
+            .iter()
+            .take(self.channels)
+            .filter(|plane| !plane.is_null())
+            .map(|&plane| unsafe { slice::from_raw_parts_mut(plane as *mut f32, frames) })
+            .collect()
+    }
+}
@@ -0,0 +1,235 @@
+use futures::{Async, Future, Poll};
+use futures::sync::oneshot;
+use std::cell::Cell;
+use std::mem;
+use std::os::raw::c_void;
+use std::sync::{Arc, Mutex};
+use libobs;
+use super::{catch_panic, RenderToken};
+
+pub trait RenderCallback {
+    fn execute(&self, cx: u32, cy: u32);
+}
+
+unsafe extern "C" fn main_render_callback<T>(cb: *mut c_void, cx: u32, cy: u32)
+where
+    T: RenderCallback,
+{
+    catch_panic("main_render_callback", || {
+        let cb = &*(cb as *mut T);
+        cb.execute(cx, cy);
+    });
+}
+
+pub unsafe fn add_main_render_callback<T>(cb: &Arc<T>)
+where
+    T: RenderCallback + Send + Sync,
+{
+    let cb = Arc::into_raw(cb.clone()) as *mut c_void;
+    libobs::obs_add_main_render_callback(Some(main_render_callback::<T>), cb);
+}
+
+pub unsafe fn remove_main_render_callback<T>(cb: &Arc<T>)
+where
+    T: RenderCallback,
+{
+    let cb = (&**cb) as *const T as *mut c_void;
+    libobs::obs_remove_main_render_callback(Some(main_render_callback::<T>), cb);
+    // decrement reference counter
+    Arc::from_raw(cb);
+}
+
+type RunOnceData<T, R, E> = (T, oneshot::Sender<Result<R, E>>);
+
+struct RunOnce<T, R, E> {
+    state: Cell<Option<RunOnceData<T, R, E>>>,
+}
+
+unsafe impl<T, R, E> Sync for RunOnce<T, R, E> {}
+
+impl<T, R, E> RenderCallback for RunOnce<T, R, E>
+where
+    T: FnOnce(&RenderToken, u32, u32) -> Result<R, E>,
+{
+    fn execute(&self, cx: u32, cy: u32) {
+        let state = self.state.take();
+        if let Some((cb, channel)) = state {
+            if channel.is_canceled() {
+                return;
+            }
+            // render callbacks are called inside the graphics context
+            let token = unsafe { RenderToken::new() };
+            let _ = channel.send(cb(&token, cx, cy));
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum RunOnceError<E> {
+    // the callback returned an error
+    Failed(E),
+    // the callback panicked, so there's no result
+    Canceled,
+}
+
+pub struct RunOnceFuture<T, R, E>
+where
+    T: FnOnce(&RenderToken, u32, u32) -> Result<R, E>,
+{
+    cb: Option<Arc<RunOnce<T, R, E>>>,
+    channel: oneshot::Receiver<Result<R, E>>,
+}
+
+impl<T, R, E> Future for RunOnceFuture<T, R, E>
+where
+    T: FnOnce(&RenderToken, u32, u32) -> Result<R, E>,
+{
+    type Item = R;
+    type Error = RunOnceError<E>;
+
+    fn poll(&mut self) -> Poll<R, RunOnceError<E>> {
+        let result = match self.channel.poll() {
+            Ok(Async::Ready(Ok(t))) => Ok(Async::Ready(t)),
+            Ok(Async::Ready(Err(t))) => Err(RunOnceError::Failed(t)),
+            Ok(Async::NotReady) => Ok(Async::NotReady),
+            // the sender was dropped while unwinding
+            Err(_) => Err(RunOnceError::Canceled),
+        };
+        match result {
+            Ok(Async::Ready(_)) | Err(_) => {
+                if let Some(cb) = self.cb.take() {
+                    unsafe {
+                        remove_main_render_callback(&cb);
+                    }
+                }
+            }
+            Ok(Async::NotReady) => {}
+        }
+        result
+    }
+}
+
+impl<T, R, E> Drop for RunOnceFuture<T, R, E>
+where
+    T: FnOnce(&RenderToken, u32, u32) -> Result<R, E>,
+{
+    fn drop(&mut self) {
+        if let Some(cb) = self.cb.take() {
+            unsafe {
+                remove_main_render_callback(&cb);
+            }
+        }
+    }
+}
+
+pub fn execute_main_render_callback<T, R, E>(cb: T) -> RunOnceFuture<T, R, E>
+where
+    T: FnOnce(&RenderToken, u32, u32) -> Result<R, E> + Send + Sized,
+    R: Send + Sized,
+    E: Send + Sized,
+{
+    let (send, receive) = oneshot::channel();
+    let cb = Arc::new(RunOnce {
+        state: Cell::new(Some((cb, send))),
+    });
+    unsafe {
+        add_main_render_callback(&cb);
+    }
+    RunOnceFuture {
+        cb: Some(cb),
+        channel: receive,
+    }
+}
+
+struct PersistentCallback(Box<Fn(&RenderToken, u32, u32) + Send + Sync>);
+
+impl RenderCallback for PersistentCallback {
+    fn execute(&self, cx: u32, cy: u32) {
+        // render callbacks are called inside the graphics context
+        let token = unsafe { RenderToken::new() };
+        (self.0)(&token, cx, cy);
+    }
+}
+
+// a main render callback that runs every frame until it's dropped
+pub struct MainRenderCallback {
+    cb: Arc<PersistentCallback>,
+}
+
+impl MainRenderCallback {
+    pub fn new<F>(cb: F) -> Self
+    where
+        F: Fn(&RenderToken, u32, u32) + Send + Sync + 'static,
+    {
+        let cb = Arc::new(PersistentCallback(Box::new(cb)));
+        unsafe {
+            add_main_render_callback(&cb);
+        }
+        MainRenderCallback { cb }
+    }
+}
+
+impl Drop for MainRenderCallback {
+    fn drop(&mut self) {
+        unsafe {
+            remove_main_render_callback(&self.cb);
+        }
+    }
+}
+
+type QueuedFn = Box<FnMut(&RenderToken) + Send>;
+
+// runs closures on the graphics thread. everything queued since the last
+// frame runs from one render callback, instead of each closure adding and
+// removing a callback of its own like `execute_main_render_callback`.
+pub struct GraphicsQueue {
+    pending: Arc<Mutex<Vec<QueuedFn>>>,
+    _callback: MainRenderCallback,
+}
+
+impl GraphicsQueue {
+    pub fn new() -> Self {
+        let pending: Arc<Mutex<Vec<QueuedFn>>> = Arc::new(Mutex::new(Vec::new()));
+        let callback_pending = pending.clone();
+        let callback = MainRenderCallback::new(move |token, _, _| {
+            // don't hold the lock while running, so the closures can queue more
+            let queued = mem::replace(&mut *callback_pending.lock().unwrap(), Vec::new());
+            for mut f in queued {
+                f(token);
+            }
+        });
+        GraphicsQueue {
+            pending,
+            _callback: callback,
+        }
+    }
+
+    // the future fails with `Canceled` if the queue is dropped before `f` runs
+    pub fn run<F, R>(&self, f: F) -> oneshot::Receiver<R>
+    where
+        F: FnOnce(&RenderToken) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let (send, receive) = oneshot::channel();
+        // boxed FnOnce can't be called, so this makes an FnMut that only works
+        // the first time
+        let mut state = Some((f, send));
+        self.pending
+            .lock()
+            .unwrap()
+            .push(Box::new(move |token: &RenderToken| {
+                if let Some((f, send)) = state.take() {
+                    if !send.is_canceled() {
+                        let _ = send.send(f(token));
+                    }
+                }
+            }));
+        receive
+    }
+}
+
+impl Default for GraphicsQueue {
+    fn default() -> Self {
+        GraphicsQueue::new()
+    }
+}
@@ -0,0 +1,674 @@
+use std::ffi::{CStr, CString};
+use std::path::Path;
+use libobs;
+
+// OBS hands paths straight to fopen on unix, so any bytes work there, but on
+// windows it expects UTF-8 and converts it to UTF-16 itself. paths that can't
+// be passed through, including ones with a nul in them, give None.
+#[cfg(unix)]
+fn path_to_cstring(path: &Path) -> Option<CString> {
+    use std::os::unix::ffi::OsStrExt;
+    CString::new(path.as_os_str().as_bytes()).ok()
+}
+
+#[cfg(not(unix))]
+fn path_to_cstring(path: &Path) -> Option<CString> {
+    path.to_str().and_then(|path| CString::new(path).ok())
+}
+
+// copies the value of `item` into `target`, using the item's default value
+// if it has no user value. objects are copied deeply so the copy does not
+// share state with the original.
+unsafe fn copy_item(
+    target: *mut libobs::obs_data_t,
+    item: *mut libobs::obs_data_item_t,
+    as_default: bool,
+) {
+    let name = libobs::obs_data_item_get_name(item);
+    let user = libobs::obs_data_item_has_user_value(item);
+    match libobs::obs_data_item_gettype(item) {
+        libobs::obs_data_type_OBS_DATA_STRING => {
+            let value = if user {
+                libobs::obs_data_item_get_string(item)
+            } else {
+                libobs::obs_data_item_get_default_string(item)
+            };
+            if as_default {
+                libobs::obs_data_set_default_string(target, name, value);
+            } else {
+                libobs::obs_data_set_string(target, name, value);
+            }
+        }
+        libobs::obs_data_type_OBS_DATA_NUMBER => {
+            let numtype = libobs::obs_data_item_numtype(item);
+            if numtype == libobs::obs_data_number_type_OBS_DATA_NUM_INT {
+                let value = if user {
+                    libobs::obs_data_item_get_int(item)
+                } else {
+                    libobs::obs_data_item_get_default_int(item)
+                };
+                if as_default {
+                    libobs::obs_data_set_default_int(target, name, value);
+                } else {
+                    libobs::obs_data_set_int(target, name, value);
+                }
+            } else {
+                let value = if user {
+                    libobs::obs_data_item_get_double(item)
+                } else {
+                    libobs::obs_data_item_get_default_double(item)
+                };
+                if as_default {
+                    libobs::obs_data_set_default_double(target, name, value);
+                } else {
+                    libobs::obs_data_set_double(target, name, value);
+                }
+            }
+        }
+        libobs::obs_data_type_OBS_DATA_BOOLEAN => {
+            let value = if user {
+                libobs::obs_data_item_get_bool(item)
+            } else {
+                libobs::obs_data_item_get_default_bool(item)
+            };
+            if as_default {
+                libobs::obs_data_set_default_bool(target, name, value);
+            } else {
+                libobs::obs_data_set_bool(target, name, value);
+            }
+        }
+        libobs::obs_data_type_OBS_DATA_OBJECT => {
+            let value = if user {
+                libobs::obs_data_item_get_obj(item)
+            } else {
+                libobs::obs_data_item_get_default_obj(item)
+            };
+            if value.is_null() {
+                return;
+            }
+            let copy = libobs::obs_data_create();
+            copy_items(copy, value, false);
+            if as_default {
+                libobs::obs_data_set_default_obj(target, name, copy);
+            } else {
+                libobs::obs_data_set_obj(target, name, copy);
+            }
+            libobs::obs_data_release(copy);
+            libobs::obs_data_release(value);
+        }
+        // OBS has no way to set a default array
+        _ => {}
+    }
+}
+
+unsafe fn copy_items(
+    target: *mut libobs::obs_data_t,
+    source: *mut libobs::obs_data_t,
+    as_default: bool,
+) {
+    let mut item = libobs::obs_data_first(source);
+    while !item.is_null() {
+        copy_item(target, item, as_default);
+        libobs::obs_data_item_next(&mut item);
+    }
+}
+
+pub struct Data(*mut libobs::obs_data_t);
+
+impl Data {
+    pub fn new() -> Self {
+        unsafe { Data(libobs::obs_data_create()) }
+    }
+
+    pub(super) unsafe fn from_raw(raw: *mut libobs::obs_data_t) -> Self {
+        Data(raw)
+    }
+
+    pub(super) unsafe fn as_raw(&self) -> *mut libobs::obs_data_t {
+        self.0
+    }
+
+    pub fn set_string(&mut self, key: &str, value: &str) {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            let value = CString::new(value).unwrap();
+            libobs::obs_data_set_string(self.0, key.as_ptr(), value.as_ptr());
+        }
+    }
+
+    pub fn get_string(&self, key: &str) -> Option<String> {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            let ptr = libobs::obs_data_get_string(self.0, key.as_ptr());
+            if ptr.is_null() {
+                None
+            } else {
+                Some(CStr::from_ptr(ptr).to_string_lossy().into_owned())
+            }
+        }
+    }
+
+    pub fn set_default_string(&mut self, key: &str, value: &str) {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            let value = CString::new(value).unwrap();
+            libobs::obs_data_set_default_string(self.0, key.as_ptr(), value.as_ptr());
+        }
+    }
+
+    pub fn get_default_string(&self, key: &str) -> Option<String> {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            let ptr = libobs::obs_data_get_default_string(self.0, key.as_ptr());
+            if ptr.is_null() {
+                None
+            } else {
+                Some(CStr::from_ptr(ptr).to_string_lossy().into_owned())
+            }
+        }
+    }
+
+    pub fn set_autoselect_string(&mut self, key: &str, value: &str) {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            let value = CString::new(value).unwrap();
+            libobs::obs_data_set_autoselect_string(self.0, key.as_ptr(), value.as_ptr());
+        }
+    }
+
+    pub fn get_autoselect_string(&self, key: &str) -> Option<String> {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            let ptr = libobs::obs_data_get_autoselect_string(self.0, key.as_ptr());
+            if ptr.is_null() {
+                None
+            } else {
+                Some(CStr::from_ptr(ptr).to_string_lossy().into_owned())
+            }
+        }
+    }
+
+    pub fn set_bool(&mut self, key: &str, value: bool) {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            libobs::obs_data_set_bool(self.0, key.as_ptr(), value);
+        }
+    }
+
+    pub fn get_bool(&self, key: &str) -> bool {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            libobs::obs_data_get_bool(self.0, key.as_ptr())
+        }
+    }
+
+    pub fn set_default_bool(&mut self, key: &str, value: bool) {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            libobs::obs_data_set_default_bool(self.0, key.as_ptr(), value);
+        }
+    }
+
+    pub fn get_default_bool(&self, key: &str) -> bool {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            libobs::obs_data_get_default_bool(self.0, key.as_ptr())
+        }
+    }
+
+    pub fn set_autoselect_bool(&mut self, key: &str, value: bool) {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            libobs::obs_data_set_autoselect_bool(self.0, key.as_ptr(), value);
+        }
+    }
+
+    pub fn get_autoselect_bool(&self, key: &str) -> bool {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            libobs::obs_data_get_autoselect_bool(self.0, key.as_ptr())
+        }
+    }
+
+    pub fn set_int(&mut self, key: &str, value: i64) {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            libobs::obs_data_set_int(self.0, key.as_ptr(), value);
+        }
+    }
+
+    pub fn get_int(&self, key: &str) -> i64 {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            libobs::obs_data_get_int(self.0, key.as_ptr())
+        }
+    }
+
+    pub fn set_default_int(&mut self, key: &str, value: i64) {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            libobs::obs_data_set_default_int(self.0, key.as_ptr(), value);
+        }
+    }
+
+    pub fn get_default_int(&self, key: &str) -> i64 {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            libobs::obs_data_get_default_int(self.0, key.as_ptr())
+        }
+    }
+
+    pub fn set_autoselect_int(&mut self, key: &str, value: i64) {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            libobs::obs_data_set_autoselect_int(self.0, key.as_ptr(), value);
+        }
+    }
+
+    pub fn get_autoselect_int(&self, key: &str) -> i64 {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            libobs::obs_data_get_autoselect_int(self.0, key.as_ptr())
+        }
+    }
+
+    pub fn set_double(&mut self, key: &str, value: f64) {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            libobs::obs_data_set_double(self.0, key.as_ptr(), value);
+        }
+    }
+
+    pub fn get_double(&self, key: &str) -> f64 {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            libobs::obs_data_get_double(self.0, key.as_ptr())
+        }
+    }
+
+    pub fn set_default_double(&mut self, key: &str, value: f64) {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            libobs::obs_data_set_default_double(self.0, key.as_ptr(), value);
+        }
+    }
+
+    pub fn get_default_double(&self, key: &str) -> f64 {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            libobs::obs_data_get_default_double(self.0, key.as_ptr())
+        }
+    }
+
+    pub fn set_autoselect_double(&mut self, key: &str, value: f64) {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            libobs::obs_data_set_autoselect_double(self.0, key.as_ptr(), value);
+        }
+    }
+
+    pub fn get_autoselect_double(&self, key: &str) -> f64 {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            libobs::obs_data_get_autoselect_double(self.0, key.as_ptr())
+        }
+    }
+
+    pub fn set_obj(&mut self, key: &str, value: &Data) {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            libobs::obs_data_set_obj(self.0, key.as_ptr(), value.0);
+        }
+    }
+
+    pub fn get_obj(&self, key: &str) -> Option<Data> {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            let ptr = libobs::obs_data_get_obj(self.0, key.as_ptr());
+            if ptr.is_null() {
+                None
+            } else {
+                Some(Data(ptr))
+            }
+        }
+    }
+
+    pub fn set_default_obj(&mut self, key: &str, value: &Data) {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            libobs::obs_data_set_default_obj(self.0, key.as_ptr(), value.0);
+        }
+    }
+
+    pub fn get_default_obj(&self, key: &str) -> Option<Data> {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            let ptr = libobs::obs_data_get_default_obj(self.0, key.as_ptr());
+            if ptr.is_null() {
+                None
+            } else {
+                Some(Data(ptr))
+            }
+        }
+    }
+
+    pub fn set_autoselect_obj(&mut self, key: &str, value: &Data) {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            libobs::obs_data_set_autoselect_obj(self.0, key.as_ptr(), value.0);
+        }
+    }
+
+    pub fn get_autoselect_obj(&self, key: &str) -> Option<Data> {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            let ptr = libobs::obs_data_get_autoselect_obj(self.0, key.as_ptr());
+            if ptr.is_null() {
+                None
+            } else {
+                Some(Data(ptr))
+            }
+        }
+    }
+
+    pub fn set_array(&mut self, key: &str, value: &DataArray) {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            libobs::obs_data_set_array(self.0, key.as_ptr(), value.0);
+        }
+    }
+
+    pub fn get_array(&self, key: &str) -> Option<DataArray> {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            let ptr = libobs::obs_data_get_array(self.0, key.as_ptr());
+            if ptr.is_null() {
+                None
+            } else {
+                Some(DataArray(ptr))
+            }
+        }
+    }
+
+    pub fn has_user_value(&self, key: &str) -> bool {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            libobs::obs_data_has_user_value(self.0, key.as_ptr())
+        }
+    }
+
+    pub fn has_default_value(&self, key: &str) -> bool {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            libobs::obs_data_has_default_value(self.0, key.as_ptr())
+        }
+    }
+
+    pub fn has_autoselect_value(&self, key: &str) -> bool {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            libobs::obs_data_has_autoselect_value(self.0, key.as_ptr())
+        }
+    }
+
+    pub fn unset_user_value(&mut self, key: &str) {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            libobs::obs_data_unset_user_value(self.0, key.as_ptr());
+        }
+    }
+
+    pub fn unset_default_value(&mut self, key: &str) {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            libobs::obs_data_unset_default_value(self.0, key.as_ptr());
+        }
+    }
+
+    pub fn unset_autoselect_value(&mut self, key: &str) {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            libobs::obs_data_unset_autoselect_value(self.0, key.as_ptr());
+        }
+    }
+
+    // removes the key entirely, including any default or autoselect value
+    pub fn erase(&mut self, key: &str) {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            libobs::obs_data_erase(self.0, key.as_ptr());
+        }
+    }
+
+    pub fn clear(&mut self) {
+        unsafe {
+            libobs::obs_data_clear(self.0);
+        }
+    }
+
+    pub fn from_json(json: &str) -> Option<Self> {
+        unsafe {
+            let json = CString::new(json).unwrap();
+            let ptr = libobs::obs_data_create_from_json(json.as_ptr());
+            if ptr.is_null() {
+                None
+            } else {
+                Some(Data(ptr))
+            }
+        }
+    }
+
+    // only user values are included
+    pub fn get_json(&self) -> String {
+        unsafe {
+            let ptr = libobs::obs_data_get_json(self.0);
+            if ptr.is_null() {
+                String::new()
+            } else {
+                CStr::from_ptr(ptr).to_string_lossy().into_owned()
+            }
+        }
+    }
+
+    // None if the file can't be read or parsed, or if OBS can't open `path`
+    pub fn from_json_file<P: AsRef<Path>>(path: P) -> Option<Self> {
+        unsafe {
+            let path = path_to_cstring(path.as_ref())?;
+            let ptr = libobs::obs_data_create_from_json_file(path.as_ptr());
+            if ptr.is_null() {
+                None
+            } else {
+                Some(Data(ptr))
+            }
+        }
+    }
+
+    // falls back to the file with `backup_ext` appended if `path` can't be read
+    pub fn from_json_file_safe<P: AsRef<Path>>(path: P, backup_ext: &str) -> Option<Self> {
+        unsafe {
+            let path = path_to_cstring(path.as_ref())?;
+            let backup_ext = CString::new(backup_ext).unwrap();
+            let ptr =
+                libobs::obs_data_create_from_json_file_safe(path.as_ptr(), backup_ext.as_ptr());
+            if ptr.is_null() {
+                None
+            } else {
+                Some(Data(ptr))
+            }
+        }
+    }
+
+    // false if the file couldn't be written, or if OBS can't open `path`
+    pub fn save_json<P: AsRef<Path>>(&self, path: P) -> bool {
+        unsafe {
+            let path = match path_to_cstring(path.as_ref()) {
+                Some(path) => path,
+                None => return false,
+            };
+            libobs::obs_data_save_json(self.0, path.as_ptr())
+        }
+    }
+
+    // writes to a temporary file first so a crash can't leave a partial
+    // file behind, keeping the previous file with `backup_ext` appended
+    pub fn save_json_safe<P: AsRef<Path>>(
+        &self,
+        path: P,
+        temp_ext: &str,
+        backup_ext: &str,
+    ) -> bool {
+        unsafe {
+            let path = match path_to_cstring(path.as_ref()) {
+                Some(path) => path,
+                None => return false,
+            };
+            let temp_ext = CString::new(temp_ext).unwrap();
+            let backup_ext = CString::new(backup_ext).unwrap();
+            libobs::obs_data_save_json_safe(
+                self.0,
+                path.as_ptr(),
+                temp_ext.as_ptr(),
+                backup_ext.as_ptr(),
+            )
+        }
+    }
+
+    // a copy that shares nothing with this one, not even nested objects, so
+    // it can be kept while the original changes. defaults become user values.
+    pub fn deep_copy(&self) -> Self {
+        let copy = Data::new();
+        unsafe {
+            copy_items(copy.0, self.0, false);
+        }
+        copy
+    }
+
+    pub fn apply(&mut self, other: &Self) {
+        unsafe {
+            libobs::obs_data_apply(self.0, other.0);
+        }
+    }
+
+    // like `apply`, but every value in `other`, whether it is a user value or
+    // a default, becomes a default here. this is what's needed to make a
+    // wrapper source start out like the source it wraps, because `apply`
+    // ignores defaults.
+    pub fn apply_defaults(&mut self, other: &Self) {
+        unsafe {
+            copy_items(self.0, other.0, true);
+        }
+    }
+}
+
+impl Clone for Data {
+    fn clone(&self) -> Self {
+        unsafe {
+            libobs::obs_data_addref(self.0);
+        }
+        Data(self.0)
+    }
+}
+
+impl Drop for Data {
+    fn drop(&mut self) {
+        unsafe {
+            libobs::obs_data_release(self.0);
+        }
+    }
+}
+
+pub struct DataArray(*mut libobs::obs_data_array_t);
+
+impl DataArray {
+    pub fn new() -> Self {
+        unsafe { DataArray(libobs::obs_data_array_create()) }
+    }
+
+    pub fn len(&self) -> usize {
+        unsafe { libobs::obs_data_array_count(self.0) }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<Data> {
+        unsafe {
+            let ptr = libobs::obs_data_array_item(self.0, index);
+            if ptr.is_null() {
+                None
+            } else {
+                Some(Data(ptr))
+            }
+        }
+    }
+
+    // returns the index of the new item
+    pub fn push(&mut self, item: &Data) -> usize {
+        unsafe { libobs::obs_data_array_push_back(self.0, item.0) }
+    }
+
+    pub fn insert(&mut self, index: usize, item: &Data) {
+        unsafe {
+            libobs::obs_data_array_insert(self.0, index, item.0);
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        unsafe {
+            libobs::obs_data_array_erase(self.0, index);
+        }
+    }
+
+    pub fn iter(&self) -> DataArrayIter {
+        DataArrayIter {
+            array: self,
+            index: 0,
+        }
+    }
+}
+
+impl Clone for DataArray {
+    fn clone(&self) -> Self {
+        unsafe {
+            libobs::obs_data_array_addref(self.0);
+        }
+        DataArray(self.0)
+    }
+}
+
+impl Drop for DataArray {
+    fn drop(&mut self) {
+        unsafe {
+            libobs::obs_data_array_release(self.0);
+        }
+    }
+}
+
+pub struct DataArrayIter<'a> {
+    array: &'a DataArray,
+    index: usize,
+}
+
+impl<'a> Iterator for DataArrayIter<'a> {
+    type Item = Data;
+
+    fn next(&mut self) -> Option<Data> {
+        if self.index < self.array.len() {
+            let item = self.array.get(self.index);
+            self.index += 1;
+            item
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a DataArray {
+    type Item = Data;
+    type IntoIter = DataArrayIter<'a>;
+
+    fn into_iter(self) -> DataArrayIter<'a> {
+        self.iter()
+    }
+}
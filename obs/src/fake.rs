@@ -0,0 +1,38 @@
+// the helpers from the fake libobs, taking the wrapper's types instead of
+// raw pointers so plugins can test themselves with `--features fake`
+
+use std::ptr;
+use libobs::fake;
+use super::{Data, ObsSource, Properties, Texture};
+
+pub use libobs::fake::{registered_source_ids, render_frame, set_version, stack_depths, take_log,
+                       StackDepths};
+
+// the names of the properties in the order they were added, including the
+// ones inside groups
+pub fn property_names(properties: &Properties) -> Vec<String> {
+    unsafe { fake::property_names(properties.as_raw()) }
+}
+
+// presses a button like the properties window would, for the properties of
+// `source` or, if it's None, of a source type. returns what the callback
+// returned, which is whether the properties should be refreshed.
+pub fn click_button(properties: &mut Properties, name: &str, source: Option<&ObsSource>) -> bool {
+    let source = source.map(|s| unsafe { s.as_raw() }).unwrap_or(ptr::null_mut());
+    unsafe { fake::click_button(properties.as_raw(), name, source) }
+}
+
+// tells a property its value changed, like the properties window would
+pub fn modify_property(properties: &mut Properties, name: &str, settings: &Data) -> bool {
+    unsafe { fake::modify_property(properties.as_raw(), name, settings.as_raw()) }
+}
+
+// the pixels of the texture, with no padding between rows
+pub fn texture_data(texture: &Texture) -> Vec<u8> {
+    unsafe { fake::texture_data(texture.as_raw()) }
+}
+
+// how many frames of audio have been output by `source`
+pub fn output_audio_frames(source: &ObsSource) -> usize {
+    unsafe { fake::output_audio_frames(source.as_raw()) }
+}
@@ -0,0 +1,244 @@
+use std::ffi::CString;
+use std::mem;
+use std::os::raw::c_void;
+use libobs;
+use super::{catch_panic, Data, ObsSource};
+
+// things that happen in the OBS UI. anything newer than this wrapper is
+// passed through as `Other`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrontendEvent {
+    StreamingStarting,
+    StreamingStarted,
+    StreamingStopping,
+    StreamingStopped,
+    RecordingStarting,
+    RecordingStarted,
+    RecordingStopping,
+    RecordingStopped,
+    SceneChanged,
+    SceneListChanged,
+    TransitionChanged,
+    TransitionStopped,
+    TransitionListChanged,
+    SceneCollectionChanged,
+    SceneCollectionListChanged,
+    ProfileChanged,
+    ProfileListChanged,
+    Exit,
+    Other(u32),
+}
+
+impl FrontendEvent {
+    fn from_raw(event: libobs::obs_frontend_event) -> Self {
+        match event {
+            libobs::obs_frontend_event_OBS_FRONTEND_EVENT_STREAMING_STARTING => {
+                FrontendEvent::StreamingStarting
+            }
+            libobs::obs_frontend_event_OBS_FRONTEND_EVENT_STREAMING_STARTED => {
+                FrontendEvent::StreamingStarted
+            }
+            libobs::obs_frontend_event_OBS_FRONTEND_EVENT_STREAMING_STOPPING => {
+                FrontendEvent::StreamingStopping
+            }
+            libobs::obs_frontend_event_OBS_FRONTEND_EVENT_STREAMING_STOPPED => {
+                FrontendEvent::StreamingStopped
+            }
+            libobs::obs_frontend_event_OBS_FRONTEND_EVENT_RECORDING_STARTING => {
+                FrontendEvent::RecordingStarting
+            }
+            libobs::obs_frontend_event_OBS_FRONTEND_EVENT_RECORDING_STARTED => {
+                FrontendEvent::RecordingStarted
+            }
+            libobs::obs_frontend_event_OBS_FRONTEND_EVENT_RECORDING_STOPPING => {
+                FrontendEvent::RecordingStopping
+            }
+            libobs::obs_frontend_event_OBS_FRONTEND_EVENT_RECORDING_STOPPED => {
+                FrontendEvent::RecordingStopped
+            }
+            libobs::obs_frontend_event_OBS_FRONTEND_EVENT_SCENE_CHANGED => {
+                FrontendEvent::SceneChanged
+            }
+            libobs::obs_frontend_event_OBS_FRONTEND_EVENT_SCENE_LIST_CHANGED => {
+                FrontendEvent::SceneListChanged
+            }
+            libobs::obs_frontend_event_OBS_FRONTEND_EVENT_TRANSITION_CHANGED => {
+                FrontendEvent::TransitionChanged
+            }
+            libobs::obs_frontend_event_OBS_FRONTEND_EVENT_TRANSITION_STOPPED => {
+                FrontendEvent::TransitionStopped
+            }
+            libobs::obs_frontend_event_OBS_FRONTEND_EVENT_TRANSITION_LIST_CHANGED => {
+                FrontendEvent::TransitionListChanged
+            }
+            libobs::obs_frontend_event_OBS_FRONTEND_EVENT_SCENE_COLLECTION_CHANGED => {
+                FrontendEvent::SceneCollectionChanged
+            }
+            libobs::obs_frontend_event_OBS_FRONTEND_EVENT_SCENE_COLLECTION_LIST_CHANGED => {
+                FrontendEvent::SceneCollectionListChanged
+            }
+            libobs::obs_frontend_event_OBS_FRONTEND_EVENT_PROFILE_CHANGED => {
+                FrontendEvent::ProfileChanged
+            }
+            libobs::obs_frontend_event_OBS_FRONTEND_EVENT_PROFILE_LIST_CHANGED => {
+                FrontendEvent::ProfileListChanged
+            }
+            libobs::obs_frontend_event_OBS_FRONTEND_EVENT_EXIT => FrontendEvent::Exit,
+            other => FrontendEvent::Other(other as u32),
+        }
+    }
+}
+
+type EventCallback = Box<Fn(FrontendEvent) + Send + Sync>;
+
+unsafe extern "C" fn event_callback(event: libobs::obs_frontend_event, data: *mut c_void) {
+    catch_panic("frontend_event_callback", || {
+        let callback = &*(data as *mut EventCallback);
+        callback(FrontendEvent::from_raw(event));
+    });
+}
+
+// `callback` runs on the UI thread until the returned value is dropped
+pub fn add_event_callback<F>(callback: F) -> FrontendEventCallback
+where
+    F: Fn(FrontendEvent) + Send + Sync + 'static,
+{
+    let callback: EventCallback = Box::new(callback);
+    let callback = Box::into_raw(Box::new(callback));
+    unsafe {
+        libobs::obs_frontend_add_event_callback(Some(event_callback), callback as *mut c_void);
+    }
+    FrontendEventCallback(callback)
+}
+
+pub struct FrontendEventCallback(*mut EventCallback);
+
+impl Drop for FrontendEventCallback {
+    fn drop(&mut self) {
+        unsafe {
+            libobs::obs_frontend_remove_event_callback(
+                Some(event_callback),
+                self.0 as *mut c_void,
+            );
+            mem::drop(Box::from_raw(self.0));
+        }
+    }
+}
+
+unsafe impl Send for FrontendEventCallback {}
+unsafe impl Sync for FrontendEventCallback {}
+
+type SaveCallback = Box<Fn(&mut Data, bool) + Send + Sync>;
+
+unsafe extern "C" fn save_callback(
+    save_data: *mut libobs::obs_data_t,
+    saving: bool,
+    data: *mut c_void,
+) {
+    catch_panic("frontend_save_callback", || {
+        let callback = &*(data as *mut SaveCallback);
+
+        // increment because our wrappers are going to decrement on drop
+        libobs::obs_data_addref(save_data);
+        callback(&mut Data::from_raw(save_data), saving);
+    });
+}
+
+// `callback` is called with `true` when the scene collection is saved, so it
+// can add its own data, and with `false` when it's loaded
+pub fn add_save_callback<F>(callback: F) -> FrontendSaveCallback
+where
+    F: Fn(&mut Data, bool) + Send + Sync + 'static,
+{
+    let callback: SaveCallback = Box::new(callback);
+    let callback = Box::into_raw(Box::new(callback));
+    unsafe {
+        libobs::obs_frontend_add_save_callback(Some(save_callback), callback as *mut c_void);
+    }
+    FrontendSaveCallback(callback)
+}
+
+pub struct FrontendSaveCallback(*mut SaveCallback);
+
+impl Drop for FrontendSaveCallback {
+    fn drop(&mut self) {
+        unsafe {
+            libobs::obs_frontend_remove_save_callback(
+                Some(save_callback),
+                self.0 as *mut c_void,
+            );
+            mem::drop(Box::from_raw(self.0));
+        }
+    }
+}
+
+unsafe impl Send for FrontendSaveCallback {}
+unsafe impl Sync for FrontendSaveCallback {}
+
+type MenuCallback = Box<Fn() + Send + Sync>;
+
+unsafe extern "C" fn menu_callback(data: *mut c_void) {
+    catch_panic("frontend_menu_callback", || {
+        let callback = &*(data as *mut MenuCallback);
+        callback();
+    });
+}
+
+// adds an item to the Tools menu. OBS has no way of removing menu items, so
+// `callback` is kept forever.
+pub fn add_tools_menu_item<F>(name: &str, callback: F)
+where
+    F: Fn() + Send + Sync + 'static,
+{
+    let name = CString::new(name).unwrap();
+    let callback: MenuCallback = Box::new(callback);
+    unsafe {
+        libobs::obs_frontend_add_tools_menu_item(
+            name.as_ptr(),
+            Some(menu_callback),
+            Box::into_raw(Box::new(callback)) as *mut c_void,
+        );
+    }
+}
+
+pub fn get_current_scene() -> Option<ObsSource> {
+    unsafe { ObsSource::from_raw(libobs::obs_frontend_get_current_scene()) }
+}
+
+pub fn set_current_scene(scene: &ObsSource) {
+    unsafe {
+        libobs::obs_frontend_set_current_scene(scene.as_raw());
+    }
+}
+
+pub fn recording_start() {
+    unsafe {
+        libobs::obs_frontend_recording_start();
+    }
+}
+
+pub fn recording_stop() {
+    unsafe {
+        libobs::obs_frontend_recording_stop();
+    }
+}
+
+pub fn recording_active() -> bool {
+    unsafe { libobs::obs_frontend_recording_active() }
+}
+
+pub fn streaming_start() {
+    unsafe {
+        libobs::obs_frontend_streaming_start();
+    }
+}
+
+pub fn streaming_stop() {
+    unsafe {
+        libobs::obs_frontend_streaming_stop();
+    }
+}
+
+pub fn streaming_active() -> bool {
+    unsafe { libobs::obs_frontend_streaming_active() }
+}
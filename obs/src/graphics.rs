@@ -0,0 +1,605 @@
+use image::RgbaImage;
+use std::ffi::{CStr, CString};
+use std::marker::PhantomData;
+use std::mem;
+use std::os::raw::{c_char, c_void};
+use std::path::Path;
+use std::{ptr, slice};
+use libobs;
+use super::{enter_graphics, RenderToken, Texture};
+
+// anything that can be given to an effect or drawn as a sprite
+pub trait GraphicsTexture {
+    // null if there's nothing to draw yet
+    unsafe fn as_raw_texture(&self) -> *mut libobs::gs_texture_t;
+}
+
+impl GraphicsTexture for Texture {
+    unsafe fn as_raw_texture(&self) -> *mut libobs::gs_texture_t {
+        self.as_raw()
+    }
+}
+
+impl GraphicsTexture for TextureRender {
+    unsafe fn as_raw_texture(&self) -> *mut libobs::gs_texture_t {
+        self.texture()
+    }
+}
+
+// the top of the matrix stack, which moves everything drawn after it changes
+pub struct Matrix<'a> {
+    _token: PhantomData<&'a RenderToken>,
+}
+
+impl<'a> Matrix<'a> {
+    pub fn identity(&self) {
+        unsafe {
+            libobs::gs_matrix_identity();
+        }
+    }
+    pub fn translate(&self, x: f32, y: f32, z: f32) {
+        unsafe {
+            libobs::gs_matrix_translate3f(x, y, z);
+        }
+    }
+    pub fn scale(&self, x: f32, y: f32, z: f32) {
+        unsafe {
+            libobs::gs_matrix_scale3f(x, y, z);
+        }
+    }
+    // clockwise around the z axis, in radians
+    pub fn rotate(&self, angle: f32) {
+        unsafe {
+            libobs::gs_matrix_rotaa4f(0.0, 0.0, 1.0, angle);
+        }
+    }
+}
+
+// a panic in a render callback is caught and the frame carries on, so
+// everything pushed or begun around a closure is undone by one of these
+// guards instead of after the closure returns
+struct MatrixGuard;
+
+impl Drop for MatrixGuard {
+    fn drop(&mut self) {
+        unsafe {
+            libobs::gs_matrix_pop();
+        }
+    }
+}
+
+struct BlendGuard;
+
+impl Drop for BlendGuard {
+    fn drop(&mut self) {
+        unsafe {
+            libobs::gs_blend_state_pop();
+        }
+    }
+}
+
+struct TextureRenderGuard(*mut libobs::gs_texrender_t);
+
+impl Drop for TextureRenderGuard {
+    fn drop(&mut self) {
+        unsafe {
+            libobs::gs_texrender_end(self.0);
+        }
+    }
+}
+
+struct TechniqueGuard(*mut libobs::gs_technique_t);
+
+impl Drop for TechniqueGuard {
+    fn drop(&mut self) {
+        unsafe {
+            libobs::gs_technique_end(self.0);
+        }
+    }
+}
+
+struct PassGuard(*mut libobs::gs_technique_t);
+
+impl Drop for PassGuard {
+    fn drop(&mut self) {
+        unsafe {
+            libobs::gs_technique_end_pass(self.0);
+        }
+    }
+}
+
+// runs `f` with a copy of the current matrix that's thrown away afterwards
+pub fn with_matrix<F, R>(_token: &RenderToken, f: F) -> R
+where
+    F: FnOnce(&Matrix) -> R,
+{
+    unsafe {
+        libobs::gs_matrix_push();
+    }
+    let _guard = MatrixGuard;
+    f(&Matrix {
+        _token: PhantomData,
+    })
+}
+
+// runs `f` with drawing moved by `x`,`y` and scaled by `scale`
+pub fn with_transform<F>(token: &RenderToken, x: f32, y: f32, scale: f32, f: F)
+where
+    F: FnOnce(),
+{
+    with_matrix(token, |matrix| {
+        matrix.translate(x, y, 0.0);
+        matrix.scale(scale, scale, 1.0);
+        f();
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlendType {
+    Zero,
+    One,
+    SrcColor,
+    InvSrcColor,
+    SrcAlpha,
+    InvSrcAlpha,
+    DstColor,
+    InvDstColor,
+    DstAlpha,
+    InvDstAlpha,
+    SrcAlphaSat,
+}
+
+impl BlendType {
+    fn as_raw(self) -> libobs::gs_blend_type {
+        match self {
+            BlendType::Zero => libobs::gs_blend_type_GS_BLEND_ZERO,
+            BlendType::One => libobs::gs_blend_type_GS_BLEND_ONE,
+            BlendType::SrcColor => libobs::gs_blend_type_GS_BLEND_SRCCOLOR,
+            BlendType::InvSrcColor => libobs::gs_blend_type_GS_BLEND_INVSRCCOLOR,
+            BlendType::SrcAlpha => libobs::gs_blend_type_GS_BLEND_SRCALPHA,
+            BlendType::InvSrcAlpha => libobs::gs_blend_type_GS_BLEND_INVSRCALPHA,
+            BlendType::DstColor => libobs::gs_blend_type_GS_BLEND_DSTCOLOR,
+            BlendType::InvDstColor => libobs::gs_blend_type_GS_BLEND_INVDSTCOLOR,
+            BlendType::DstAlpha => libobs::gs_blend_type_GS_BLEND_DSTALPHA,
+            BlendType::InvDstAlpha => libobs::gs_blend_type_GS_BLEND_INVDSTALPHA,
+            BlendType::SrcAlphaSat => libobs::gs_blend_type_GS_BLEND_SRCALPHASAT,
+        }
+    }
+}
+
+// runs `f` with blending turned on and set to `src` and `dest`. the previous
+// blend state is restored afterwards.
+pub fn with_blend<F, R>(_token: &RenderToken, src: BlendType, dest: BlendType, f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    unsafe {
+        libobs::gs_blend_state_push();
+        libobs::gs_enable_blending(true);
+        libobs::gs_blend_function(src.as_raw(), dest.as_raw());
+    }
+    let _guard = BlendGuard;
+    f()
+}
+
+// runs `f` with blending turned off, so drawing replaces what's there
+pub fn without_blend<F, R>(_token: &RenderToken, f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    unsafe {
+        libobs::gs_blend_state_push();
+        libobs::gs_enable_blending(false);
+    }
+    let _guard = BlendGuard;
+    f()
+}
+
+// draws `texture` at 0,0 with the current effect. a `width` or `height` of 0
+// uses the size of the texture.
+pub fn draw_sprite<T>(_token: &RenderToken, texture: &T, width: u32, height: u32)
+where
+    T: GraphicsTexture,
+{
+    unsafe {
+        let tex = texture.as_raw_texture();
+        if !tex.is_null() {
+            libobs::gs_draw_sprite(tex, 0, width, height);
+        }
+    }
+}
+
+// fills a rectangle with `color`, which is red, green, blue and alpha from 0
+// to 1
+pub fn draw_rect(token: &RenderToken, color: [f32; 4], x: f32, y: f32, width: u32, height: u32) {
+    if width == 0 || height == 0 {
+        return;
+    }
+    let solid = Effect::base(BaseEffect::Solid, token);
+    if let Some(param) = solid.param("color", token) {
+        param.set_vec4(color);
+    }
+    solid.loop_technique("Solid", token, || {
+        with_matrix(token, |matrix| {
+            matrix.translate(x, y, 0.0);
+            unsafe {
+                libobs::gs_draw_sprite(ptr::null_mut(), 0, width, height);
+            }
+        })
+    });
+}
+
+pub struct TextureRender(*mut libobs::gs_texrender_t);
+
+impl TextureRender {
+    pub fn new(_token: &RenderToken) -> Self {
+        unsafe {
+            TextureRender(libobs::gs_texrender_create(
+                libobs::gs_color_format_GS_RGBA,
+                libobs::gs_zstencil_format_GS_ZS_NONE,
+            ))
+        }
+    }
+
+    // renders `f` into the texture. `offset_x` and `offset_y` move the
+    // content relative to the top left corner; anything outside of
+    // `width`x`height` is clipped.
+    pub fn render<F>(
+        &mut self,
+        _token: &RenderToken,
+        width: u32,
+        height: u32,
+        offset_x: f32,
+        offset_y: f32,
+        f: F,
+    ) -> bool
+    where
+        F: FnOnce(),
+    {
+        unsafe {
+            libobs::gs_texrender_reset(self.0);
+            if !libobs::gs_texrender_begin(self.0, width, height) {
+                return false;
+            }
+        }
+        let _texrender = TextureRenderGuard(self.0);
+        unsafe {
+            let clear: libobs::vec4 = mem::zeroed();
+            libobs::gs_clear(libobs::GS_CLEAR_COLOR, &clear, 0.0, 0);
+            libobs::gs_ortho(
+                -offset_x,
+                width as f32 - offset_x,
+                -offset_y,
+                height as f32 - offset_y,
+                -100.0,
+                100.0,
+            );
+            libobs::gs_blend_state_push();
+            libobs::gs_blend_function(
+                libobs::gs_blend_type_GS_BLEND_ONE,
+                libobs::gs_blend_type_GS_BLEND_ZERO,
+            );
+        }
+        let _blend = BlendGuard;
+        f();
+        true
+    }
+
+    fn texture(&self) -> *mut libobs::gs_texture_t {
+        unsafe { libobs::gs_texrender_get_texture(self.0) }
+    }
+}
+
+impl Drop for TextureRender {
+    fn drop(&mut self) {
+        let _graphics = enter_graphics();
+        unsafe {
+            libobs::gs_texrender_destroy(self.0);
+        }
+    }
+}
+
+// copies rendered textures back to memory. mapping waits for the GPU to catch
+// up, so this is for screenshots and the like rather than every frame.
+pub struct StageSurface {
+    raw: *mut libobs::gs_stagesurf_t,
+    width: u32,
+    height: u32,
+}
+
+impl StageSurface {
+    pub fn new(width: u32, height: u32, _token: &RenderToken) -> Self {
+        StageSurface {
+            raw: unsafe {
+                libobs::gs_stagesurface_create(width, height, libobs::gs_color_format_GS_RGBA)
+            },
+            width,
+            height,
+        }
+    }
+
+    // `texture` has to be RGBA and the same size as the surface
+    pub fn stage<T>(&mut self, texture: &T, _token: &RenderToken)
+    where
+        T: GraphicsTexture,
+    {
+        unsafe {
+            let tex = texture.as_raw_texture();
+            if !tex.is_null() {
+                libobs::gs_stage_texture(self.raw, tex);
+            }
+        }
+    }
+
+    // the contents as of the last `stage`
+    pub fn read(&mut self, _token: &RenderToken) -> Option<RgbaImage> {
+        unsafe {
+            let mut data: *mut u8 = ptr::null_mut();
+            let mut linesize = 0u32;
+            if !libobs::gs_stagesurface_map(self.raw, &mut data, &mut linesize) {
+                return None;
+            }
+            let row = self.width as usize * 4;
+            let mut pixels = Vec::with_capacity(row * self.height as usize);
+            for y in 0..self.height as usize {
+                let line = data.offset((y * linesize as usize) as isize);
+                pixels.extend_from_slice(slice::from_raw_parts(line, row));
+            }
+            libobs::gs_stagesurface_unmap(self.raw);
+            RgbaImage::from_raw(self.width, self.height, pixels)
+        }
+    }
+}
+
+impl Drop for StageSurface {
+    fn drop(&mut self) {
+        let _graphics = enter_graphics();
+        unsafe {
+            libobs::gs_stagesurface_destroy(self.raw);
+        }
+    }
+}
+
+// the effects OBS loads for itself
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BaseEffect {
+    Default,
+    DefaultRect,
+    Opaque,
+    Solid,
+    Bicubic,
+    Lanczos,
+}
+
+impl BaseEffect {
+    fn as_raw(self) -> libobs::obs_base_effect {
+        match self {
+            BaseEffect::Default => libobs::obs_base_effect_OBS_EFFECT_DEFAULT,
+            BaseEffect::DefaultRect => libobs::obs_base_effect_OBS_EFFECT_DEFAULT_RECT,
+            BaseEffect::Opaque => libobs::obs_base_effect_OBS_EFFECT_OPAQUE,
+            BaseEffect::Solid => libobs::obs_base_effect_OBS_EFFECT_SOLID,
+            BaseEffect::Bicubic => libobs::obs_base_effect_OBS_EFFECT_BICUBIC,
+            BaseEffect::Lanczos => libobs::obs_base_effect_OBS_EFFECT_LANCZOS,
+        }
+    }
+}
+
+pub struct Effect {
+    raw: *mut libobs::gs_effect_t,
+    // base effects belong to OBS
+    owned: bool,
+}
+
+impl Effect {
+    unsafe fn from_created(
+        effect: *mut libobs::gs_effect_t,
+        error: *mut c_char,
+    ) -> Result<Self, String> {
+        let message = if error.is_null() {
+            None
+        } else {
+            let message = CStr::from_ptr(error).to_string_lossy().into_owned();
+            libobs::bfree(error as *mut _);
+            Some(message)
+        };
+        if effect.is_null() {
+            Err(message.unwrap_or_else(|| "unknown error".to_string()))
+        } else {
+            Ok(Effect {
+                raw: effect,
+                owned: true,
+            })
+        }
+    }
+
+    pub fn from_string(source: &str, name: &str, _token: &RenderToken) -> Result<Self, String> {
+        let source = CString::new(source).map_err(|e| e.to_string())?;
+        let name = CString::new(name).map_err(|e| e.to_string())?;
+        let mut error: *mut c_char = ptr::null_mut();
+        unsafe {
+            let effect = libobs::gs_effect_create(source.as_ptr(), name.as_ptr(), &mut error);
+            Effect::from_created(effect, error)
+        }
+    }
+
+    pub fn from_file(path: &Path, _token: &RenderToken) -> Result<Self, String> {
+        let file = path.to_str()
+            .and_then(|path| CString::new(path).ok())
+            .ok_or_else(|| format!("cannot load an effect from {:?}", path))?;
+        let mut error: *mut c_char = ptr::null_mut();
+        unsafe {
+            let effect = libobs::gs_effect_create_from_file(file.as_ptr(), &mut error);
+            Effect::from_created(effect, error)
+        }
+    }
+
+    pub fn base(kind: BaseEffect, _token: &RenderToken) -> Self {
+        unsafe {
+            Effect {
+                raw: libobs::obs_get_base_effect(kind.as_raw()),
+                owned: false,
+            }
+        }
+    }
+
+    pub(super) unsafe fn as_raw(&self) -> *mut libobs::gs_effect_t {
+        self.raw
+    }
+
+    pub fn param<'a>(&'a self, name: &str, _token: &'a RenderToken) -> Option<EffectParam<'a>> {
+        let name = CString::new(name).unwrap();
+        let param = unsafe { libobs::gs_effect_get_param_by_name(self.raw, name.as_ptr()) };
+        if param.is_null() {
+            None
+        } else {
+            Some(EffectParam {
+                raw: param,
+                _effect: PhantomData,
+            })
+        }
+    }
+
+    pub fn technique<'a>(&'a self, name: &str, _token: &'a RenderToken) -> Option<Technique<'a>> {
+        let name = CString::new(name).unwrap();
+        let technique = unsafe { libobs::gs_effect_get_technique(self.raw, name.as_ptr()) };
+        if technique.is_null() {
+            None
+        } else {
+            Some(Technique {
+                raw: technique,
+                _effect: PhantomData,
+            })
+        }
+    }
+
+    // calls `f` once for each pass of `technique`, if the effect has it
+    pub fn loop_technique<F>(&self, technique: &str, token: &RenderToken, mut f: F)
+    where
+        F: FnMut(),
+    {
+        if let Some(technique) = self.technique(technique, token) {
+            technique.draw(|_| f());
+        }
+    }
+
+    pub fn set_float(&mut self, name: &str, value: f32, token: &RenderToken) {
+        if let Some(param) = self.param(name, token) {
+            param.set_float(value);
+        }
+    }
+
+    // draws `texture` as a sprite using the effect's `image` parameter and the
+    // given technique.
+    pub fn draw_sprite<T>(
+        &mut self,
+        technique: &str,
+        texture: &T,
+        width: u32,
+        height: u32,
+        token: &RenderToken,
+    ) where
+        T: GraphicsTexture,
+    {
+        if unsafe { texture.as_raw_texture() }.is_null() {
+            return;
+        }
+        if let Some(param) = self.param("image", token) {
+            param.set_texture(texture);
+        }
+        self.loop_technique(technique, token, || draw_sprite(token, texture, width, height));
+    }
+}
+
+impl Drop for Effect {
+    fn drop(&mut self) {
+        if self.owned {
+            let _graphics = enter_graphics();
+            unsafe {
+                libobs::gs_effect_destroy(self.raw);
+            }
+        }
+    }
+}
+
+// a uniform in an effect. values are uploaded when the effect is next used.
+// like the `RenderToken` it came from, it can't leave the graphics context.
+pub struct EffectParam<'a> {
+    raw: *mut libobs::gs_eparam_t,
+    _effect: PhantomData<(&'a Effect, &'a RenderToken)>,
+}
+
+impl<'a> EffectParam<'a> {
+    fn set_val<T>(&self, value: &T) {
+        unsafe {
+            libobs::gs_effect_set_val(
+                self.raw,
+                value as *const T as *const c_void,
+                mem::size_of::<T>(),
+            );
+        }
+    }
+    pub fn set_bool(&self, value: bool) {
+        unsafe {
+            libobs::gs_effect_set_bool(self.raw, value);
+        }
+    }
+    pub fn set_int(&self, value: i32) {
+        unsafe {
+            libobs::gs_effect_set_int(self.raw, value);
+        }
+    }
+    pub fn set_float(&self, value: f32) {
+        unsafe {
+            libobs::gs_effect_set_float(self.raw, value);
+        }
+    }
+    pub fn set_vec2(&self, value: [f32; 2]) {
+        self.set_val(&value);
+    }
+    pub fn set_vec3(&self, value: [f32; 3]) {
+        self.set_val(&value);
+    }
+    pub fn set_vec4(&self, value: [f32; 4]) {
+        self.set_val(&value);
+    }
+    // row major, like OBS's matrix4
+    pub fn set_matrix4(&self, value: [f32; 16]) {
+        self.set_val(&value);
+    }
+    pub fn set_texture<T>(&self, texture: &T)
+    where
+        T: GraphicsTexture,
+    {
+        unsafe {
+            libobs::gs_effect_set_texture(self.raw, texture.as_raw_texture());
+        }
+    }
+    // back to the value in the effect file
+    pub fn set_default(&self) {
+        unsafe {
+            libobs::gs_effect_set_default(self.raw);
+        }
+    }
+}
+
+pub struct Technique<'a> {
+    raw: *mut libobs::gs_technique_t,
+    _effect: PhantomData<(&'a Effect, &'a RenderToken)>,
+}
+
+impl<'a> Technique<'a> {
+    // calls `f` with the index of each pass, with that pass active
+    pub fn draw<F>(&self, mut f: F)
+    where
+        F: FnMut(usize),
+    {
+        let passes = unsafe { libobs::gs_technique_begin(self.raw) };
+        let _technique = TechniqueGuard(self.raw);
+        for pass in 0..passes {
+            if unsafe { libobs::gs_technique_begin_pass(self.raw, pass) } {
+                let _pass = PassGuard(self.raw);
+                f(pass);
+            }
+        }
+    }
+}
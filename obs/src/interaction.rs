@@ -0,0 +1,65 @@
+use std::ffi::CStr;
+use libobs;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right,
+}
+
+impl MouseButton {
+    pub(super) fn from_raw(raw: i32) -> Option<Self> {
+        match raw as libobs::obs_mouse_button_type {
+            libobs::obs_mouse_button_type_MOUSE_LEFT => Some(MouseButton::Left),
+            libobs::obs_mouse_button_type_MOUSE_MIDDLE => Some(MouseButton::Middle),
+            libobs::obs_mouse_button_type_MOUSE_RIGHT => Some(MouseButton::Right),
+            _ => None,
+        }
+    }
+}
+
+// `x` and `y` are relative to the top left corner of the source
+#[derive(Clone, Debug, PartialEq)]
+pub struct MouseEvent {
+    // the INTERACT_* flags
+    pub modifiers: u32,
+    pub x: i32,
+    pub y: i32,
+}
+
+impl MouseEvent {
+    pub(super) fn from_raw(event: &libobs::obs_mouse_event) -> Self {
+        MouseEvent {
+            modifiers: event.modifiers,
+            x: event.x,
+            y: event.y,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyEvent {
+    // the INTERACT_* flags
+    pub modifiers: u32,
+    pub text: String,
+    pub native_modifiers: u32,
+    pub native_scancode: u32,
+    pub native_vkey: u32,
+}
+
+impl KeyEvent {
+    pub(super) unsafe fn from_raw(event: &libobs::obs_key_event) -> Self {
+        KeyEvent {
+            modifiers: event.modifiers,
+            text: if event.text.is_null() {
+                String::new()
+            } else {
+                CStr::from_ptr(event.text).to_string_lossy().into_owned()
+            },
+            native_modifiers: event.native_modifiers,
+            native_scancode: event.native_scancode,
+            native_vkey: event.native_vkey,
+        }
+    }
+}
@@ -0,0 +1,101 @@
+// a wrapper for writing OBS Studio plugins in Rust. a plugin is a cdylib that
+// declares itself with `obs_declare_module!` and registers its sources when
+// OBS loads it; see examples/color-source.rs.
+
+extern crate futures;
+extern crate image;
+#[macro_use]
+extern crate lazy_static;
+extern crate libobs_sys as libobs;
+#[macro_use]
+extern crate log;
+
+mod macros;
+
+mod audio;
+mod callback;
+mod data;
+#[cfg(feature = "fake")]
+pub mod fake;
+#[cfg(feature = "frontend")]
+pub mod frontend;
+mod graphics;
+mod interaction;
+mod logger;
+mod lookup;
+mod panic;
+mod properties;
+mod render;
+mod settings;
+mod signal;
+mod source;
+mod symbols;
+mod texture;
+mod version;
+
+use libobs;
+
+pub use self::audio::{get_audio_info, get_time_ns, AudioFormat, AudioFrame, AudioInfo,
+                      FilterAudio, SpeakerLayout};
+pub use self::callback::{execute_main_render_callback, GraphicsQueue, MainRenderCallback,
+                         RunOnceError};
+pub use self::data::{Data, DataArray, DataArrayIter};
+pub use self::graphics::{draw_rect, draw_sprite, with_blend, with_matrix, with_transform,
+                         without_blend, BaseEffect, BlendType, Effect, EffectParam,
+                         GraphicsTexture, Matrix, StageSurface, Technique, TextureRender};
+pub use self::interaction::{KeyEvent, MouseButton, MouseEvent};
+pub use libobs::{obs_module_t, obs_text_type, LIBOBS_API_MAJOR_VER, LIBOBS_API_MINOR_VER,
+                 LIBOBS_API_PATCH_VER, OBS_SOURCE_ASYNC, OBS_SOURCE_AUDIO, OBS_SOURCE_INTERACTION,
+                 OBS_SOURCE_VIDEO};
+pub use self::logger::{blog, install_logger, max_log_level, set_max_log_level};
+pub use self::lookup::{free_module_locale, load_locale, module_get_string, module_text,
+                       set_module_locale, Lookup};
+pub use self::panic::catch_panic;
+pub use self::properties::{ComboFormat, ComboType, EditableListType, GroupType, PathType,
+                           Properties, Property, TextType};
+pub use self::render::{RenderCell, RenderToken};
+pub use self::settings::{PropertyOptions, Settings, SettingsValue};
+pub use self::signal::{get_proc_handler, get_signal_handler, CallData, ProcHandler,
+                       SignalConnection, SignalHandler};
+pub use self::source::{enum_input_types, get_source_defaults, register_audio_source,
+                       register_filter, register_source, source_create_private, AudioSource,
+                       AudioSourceDefinition, FilterSource, FilterSourceDefinition, ObsFilter,
+                       ObsSource, ObsWeakSource, SourceType, VideoSource,
+                       VideoSourceDefinition};
+pub use self::texture::{ColorFormat, Texture, TextureMap};
+pub use self::version::{check_version, compiled_version, runtime_version, ObsVersion,
+                        MINIMUM_VERSION};
+
+// the plugin itself. it's created when OBS loads the plugin and dropped when
+// it's unloaded; returning None from `load` makes loading fail.
+pub trait Module<T>
+where
+    T: Module<T>,
+{
+    fn load() -> Option<T>;
+}
+
+// the token isn't Send, so neither is the handle, and the graphics context is
+// left on the thread that entered it
+pub struct GraphicsHandle(RenderToken);
+
+impl GraphicsHandle {
+    pub fn token(&self) -> &RenderToken {
+        &self.0
+    }
+}
+
+impl Drop for GraphicsHandle {
+    fn drop(&mut self) {
+        unsafe {
+            libobs::obs_leave_graphics();
+        }
+    }
+}
+
+pub fn enter_graphics() -> GraphicsHandle {
+    unsafe {
+        libobs::obs_enter_graphics();
+        GraphicsHandle(RenderToken::new())
+    }
+}
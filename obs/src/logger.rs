@@ -0,0 +1,127 @@
+use log::{self as rlog, Level, LevelFilter, Log, Metadata, Record};
+use std::ffi::CString;
+use std::os::raw::c_char;
+use std::sync::RwLock;
+use libobs;
+
+const LOG_TEMPLATE: *const c_char = b"[%s] %s\0" as *const u8 as *const c_char;
+
+// records from this crate or the plugin have a target starting with one of
+// these. anything else comes from a dependency and gets its target included
+// in the message.
+const CRATE_TARGET: &str = module_path!();
+
+// the plugin doing the logging, set by install_logger
+struct LogModule {
+    name: CString,
+    root: String,
+}
+
+lazy_static! {
+    static ref MODULE: RwLock<LogModule> = RwLock::new(LogModule {
+        name: CString::new("obs").unwrap(),
+        root: String::new(),
+    });
+}
+
+fn blog_as(name: &CString, level: i32, text: String) {
+    unsafe {
+        // this is how panics get reported, so it mustn't panic itself
+        let text = CString::new(text.replace('\0', "\\0")).unwrap();
+        libobs::blog(level, LOG_TEMPLATE, name.as_ptr(), text.as_ptr());
+    }
+}
+
+pub fn blog(level: i32, text: String) {
+    let module = match MODULE.read() {
+        Ok(module) => module,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    blog_as(&module.name, level, text);
+}
+
+fn obs_level(level: Level) -> i32 {
+    match level {
+        Level::Error => libobs::LOG_ERROR as i32,
+        Level::Warn => libobs::LOG_WARNING as i32,
+        Level::Info => libobs::LOG_INFO as i32,
+        Level::Debug | Level::Trace => libobs::LOG_DEBUG as i32,
+    }
+}
+
+fn crate_root(target: &str) -> &str {
+    target.split("::").next().unwrap_or(target)
+}
+
+fn has_root(target: &str, root: &str) -> bool {
+    !root.is_empty()
+        && target.starts_with(root)
+        && (target.len() == root.len() || target[root.len()..].starts_with("::"))
+}
+
+fn is_own_target(target: &str, module: &LogModule) -> bool {
+    has_root(target, crate_root(CRATE_TARGET)) || has_root(target, &module.root)
+}
+
+struct ObsLogger;
+
+impl Log for ObsLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= rlog::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let module = match MODULE.read() {
+            Ok(module) => module,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let text = if is_own_target(record.target(), &module) {
+            format!("{}", record.args())
+        } else {
+            format!("{}: {}", record.target(), record.args())
+        };
+        blog_as(&module.name, obs_level(record.level()), text);
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: ObsLogger = ObsLogger;
+
+// sends everything logged through the log crate, including from
+// dependencies, to the OBS log with `name` in front. `target` is any module
+// path in the plugin. the level comes from an environment variable named
+// after the plugin, like GPMDP_LOG, if it's set.
+pub fn install_logger(name: &str, target: &str) {
+    {
+        let mut module = match MODULE.write() {
+            Ok(module) => module,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        *module = LogModule {
+            name: CString::new(name.replace('\0', "")).unwrap(),
+            root: crate_root(target).to_string(),
+        };
+    }
+    // fails if a logger is already installed, which happens when the module
+    // is loaded a second time in the same process. the old one is still ours.
+    let _ = rlog::set_logger(&LOGGER);
+    let variable = format!("{}_LOG", name.to_uppercase().replace('-', "_"));
+    let level = ::std::env::var(variable)
+        .ok()
+        .and_then(|level| level.parse().ok())
+        .unwrap_or(LevelFilter::Info);
+    set_max_log_level(level);
+}
+
+// records above this level are skipped before they're formatted
+pub fn set_max_log_level(level: LevelFilter) {
+    rlog::set_max_level(level);
+}
+
+pub fn max_log_level() -> LevelFilter {
+    rlog::max_level()
+}
@@ -0,0 +1,88 @@
+use std::borrow::Cow;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::ptr;
+use std::sync::RwLock;
+use libobs;
+
+pub struct Lookup(*mut libobs::lookup_t);
+
+impl Lookup {
+    pub(super) fn from_raw(lookup: *mut libobs::lookup_t) -> Self {
+        Lookup(lookup)
+    }
+
+    pub fn getstr(&self, val: &str) -> Option<String> {
+        let val = CString::new(val).ok()?;
+        unsafe {
+            let mut ptr: *const c_char = ptr::null();
+            if libobs::text_lookup_getstr(self.0, val.as_ptr(), &mut ptr) {
+                Some(CStr::from_ptr(ptr).to_string_lossy().into_owned())
+            } else {
+                None
+            }
+        }
+    }
+}
+
+impl Drop for Lookup {
+    fn drop(&mut self) {
+        unsafe {
+            libobs::text_lookup_destroy(self.0);
+        }
+    }
+}
+
+unsafe impl Send for Lookup {}
+unsafe impl Sync for Lookup {}
+
+lazy_static! {
+    // the plugin's translations for the current locale. every plugin links its
+    // own copy of this crate, so there's one of these per plugin.
+    static ref MODULE_LOOKUP: RwLock<Option<Lookup>> = RwLock::new(None);
+}
+
+pub unsafe fn load_locale(
+    module: *mut libobs::obs_module_t,
+    default_locale: *const c_char,
+    locale: *const c_char,
+) -> Lookup {
+    Lookup::from_raw(libobs::obs_module_load_locale(
+        module,
+        default_locale,
+        locale,
+    ))
+}
+
+// called by obs_module_set_locale from `obs_module_use_default_locale!`
+pub unsafe fn set_module_locale(
+    module: *mut libobs::obs_module_t,
+    default_locale: *const c_char,
+    locale: *const c_char,
+) {
+    let lookup = load_locale(module, default_locale, locale);
+    *MODULE_LOOKUP.write().unwrap() = Some(lookup);
+}
+
+pub fn free_module_locale() {
+    *MODULE_LOOKUP.write().unwrap() = None;
+}
+
+// the translation of `val`, or `val` itself if there isn't one
+pub fn module_text(val: &str) -> Cow<str> {
+    match *MODULE_LOOKUP.read().unwrap() {
+        Some(ref lookup) => match lookup.getstr(val) {
+            Some(out) => Cow::Owned(out),
+            None => Cow::Borrowed(val),
+        },
+        None => Cow::Borrowed(val),
+    }
+}
+
+pub fn module_get_string(val: &str) -> Option<String> {
+    MODULE_LOOKUP
+        .read()
+        .unwrap()
+        .as_ref()
+        .and_then(|lookup| lookup.getstr(val))
+}
@@ -0,0 +1,95 @@
+#![macro_use]
+
+#[macro_export]
+macro_rules! obs_declare_module {
+    ($type:ty, $name:expr, $description:expr) => {
+        static mut OBS_MODULE_POINTER: ::std::option::Option<*mut $crate::obs_module_t> =
+            ::std::option::Option::None;
+        const OBS_MODULE_NAME: &'static str = concat!($name, "\0");
+        const OBS_MODULE_DESCRIPTION: &'static str = concat!($description, "\0");
+        static mut MODULE_VALUE: ::std::option::Option<$type> = ::std::option::Option::None;
+
+        #[no_mangle]
+        pub unsafe extern "C" fn obs_module_set_pointer(module: *mut $crate::obs_module_t) -> () {
+            OBS_MODULE_POINTER = ::std::option::Option::Some(module);
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn obs_module_ver() -> u32 {
+            $crate::compiled_version().packed()
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn obs_module_name() -> *const ::std::os::raw::c_char {
+            OBS_MODULE_NAME.as_bytes().as_ptr() as *const ::std::os::raw::c_char
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn obs_module_description() -> *const ::std::os::raw::c_char {
+            OBS_MODULE_DESCRIPTION.as_bytes().as_ptr() as *const ::std::os::raw::c_char
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn obs_module_load() -> bool {
+            $crate::catch_panic("obs_module_load", || {
+                $crate::install_logger($name, module_path!());
+                if !$crate::check_version() {
+                    return false;
+                }
+                MODULE_VALUE = <$type as $crate::Module<$type>>::load();
+                MODULE_VALUE.is_some()
+            }).unwrap_or(false)
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn obs_module_unload() -> () {
+            $crate::catch_panic("obs_module_unload", || {
+                MODULE_VALUE = ::std::option::Option::None;
+            });
+        }
+    };
+    ($type:ty, $name:expr, $description:expr, $author:expr) => {
+        $crate::obs_declare_module!($type, $name, $description);
+
+        const OBS_MODULE_AUTHOR: &'static str = concat!($author, "\0");
+
+        #[no_mangle]
+        pub unsafe extern "C" fn obs_module_author() -> *const ::std::os::raw::c_char {
+            OBS_MODULE_AUTHOR.as_bytes().as_ptr() as *const ::std::os::raw::c_char
+        }
+    }
+}
+
+#[macro_export]
+macro_rules! obs_module_use_default_locale {
+    ($locale:expr) => {
+        const OBS_MODULE_DEFAULT_LOCALE: &'static str = concat!($locale, "\0");
+
+        pub fn obs_module_text(val: &str) -> ::std::borrow::Cow<str> {
+            $crate::module_text(val)
+        }
+
+        // None if the locale doesn't have `val`
+        pub fn obs_module_get_string(val: &str) -> ::std::option::Option<String> {
+            $crate::module_get_string(val)
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn obs_module_set_locale(locale: *const ::std::os::raw::c_char) {
+            $crate::catch_panic("obs_module_set_locale", || {
+                $crate::set_module_locale(
+                    OBS_MODULE_POINTER.unwrap(),
+                    OBS_MODULE_DEFAULT_LOCALE.as_bytes().as_ptr() as *const ::std::os::raw::c_char,
+                    locale,
+                )
+            });
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn obs_module_free_locale() {
+            $crate::catch_panic("obs_module_free_locale", || {
+                $crate::free_module_locale();
+            });
+        }
+    };
+}
@@ -0,0 +1,34 @@
+use libobs;
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use super::blog;
+
+fn panic_message(payload: &Box<Any + Send>) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        *message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.as_str()
+    } else {
+        "unknown panic"
+    }
+}
+
+// unwinding into OBS is undefined behavior, so everything OBS calls goes
+// through here. returns None if `f` panicked, and the caller has to come up
+// with something safe to give back to OBS.
+pub fn catch_panic<F, R>(context: &str, f: F) -> Option<R>
+where
+    F: FnOnce() -> R,
+{
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(result) => Some(result),
+        Err(payload) => {
+            // straight to blog in case the logger isn't installed yet
+            blog(
+                libobs::LOG_ERROR as i32,
+                format!("panic in {}: {}", context, panic_message(&payload)),
+            );
+            None
+        }
+    }
+}
@@ -0,0 +1,705 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::marker::PhantomData;
+use std::mem;
+use std::os::raw::{c_char, c_int, c_void};
+use std::ptr;
+use std::rc::Rc;
+use libobs;
+use super::{catch_panic, runtime_version, symbols, Data, ObsSource};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextType {
+    Default,
+    Password,
+    Multiline,
+}
+
+impl TextType {
+    fn as_raw(self) -> libobs::obs_text_type {
+        match self {
+            TextType::Default => libobs::obs_text_type_OBS_TEXT_DEFAULT,
+            TextType::Password => libobs::obs_text_type_OBS_TEXT_PASSWORD,
+            TextType::Multiline => libobs::obs_text_type_OBS_TEXT_MULTILINE,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PathType {
+    File,
+    FileSave,
+    Directory,
+}
+
+impl PathType {
+    fn as_raw(self) -> libobs::obs_path_type {
+        match self {
+            PathType::File => libobs::obs_path_type_OBS_PATH_FILE,
+            PathType::FileSave => libobs::obs_path_type_OBS_PATH_FILE_SAVE,
+            PathType::Directory => libobs::obs_path_type_OBS_PATH_DIRECTORY,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ComboType {
+    // the user can type in their own value
+    Editable,
+    List,
+}
+
+impl ComboType {
+    fn as_raw(self) -> libobs::obs_combo_type {
+        match self {
+            ComboType::Editable => libobs::obs_combo_type_OBS_COMBO_TYPE_EDITABLE,
+            ComboType::List => libobs::obs_combo_type_OBS_COMBO_TYPE_LIST,
+        }
+    }
+}
+
+// the type of the values stored in the settings for a list
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ComboFormat {
+    Int,
+    Float,
+    String,
+}
+
+impl ComboFormat {
+    fn as_raw(self) -> libobs::obs_combo_format {
+        match self {
+            ComboFormat::Int => libobs::obs_combo_format_OBS_COMBO_FORMAT_INT,
+            ComboFormat::Float => libobs::obs_combo_format_OBS_COMBO_FORMAT_FLOAT,
+            ComboFormat::String => libobs::obs_combo_format_OBS_COMBO_FORMAT_STRING,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EditableListType {
+    Strings,
+    Files,
+    FilesAndUrls,
+}
+
+impl EditableListType {
+    fn as_raw(self) -> libobs::obs_editable_list_type {
+        match self {
+            EditableListType::Strings => {
+                libobs::obs_editable_list_type_OBS_EDITABLE_LIST_TYPE_STRINGS
+            }
+            EditableListType::Files => {
+                libobs::obs_editable_list_type_OBS_EDITABLE_LIST_TYPE_FILES
+            }
+            EditableListType::FilesAndUrls => {
+                libobs::obs_editable_list_type_OBS_EDITABLE_LIST_TYPE_FILES_AND_URLS
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GroupType {
+    Normal,
+    // the group has a checkbox stored as a bool under the group's name
+    Checkable,
+}
+
+impl GroupType {
+    fn as_raw(self) -> c_int {
+        match self {
+            GroupType::Normal => symbols::OBS_GROUP_NORMAL,
+            GroupType::Checkable => symbols::OBS_GROUP_CHECKABLE,
+        }
+    }
+}
+
+type ButtonCallback = Rc<Fn(&mut Properties, Option<&ObsSource>) -> bool>;
+type ModifiedCallback = Rc<Fn(&mut Properties, &Data) -> bool>;
+
+// closures are kept in the param of the obs_properties_t they were added to
+// and looked up by property name when OBS calls back. OBS calls back with the
+// group a property is in, so lookups start there and walk up to the root.
+#[derive(Default)]
+struct Callbacks {
+    buttons: HashMap<String, ButtonCallback>,
+    modified: HashMap<String, ModifiedCallback>,
+}
+
+unsafe extern "C" fn free_callbacks(param: *mut c_void) {
+    catch_panic("free_callbacks", || {
+        mem::drop(Box::from_raw(param as *mut RefCell<Callbacks>));
+    });
+}
+
+// returns null if the param is already used by whoever created the properties
+unsafe fn install_callbacks(raw: *mut libobs::obs_properties_t) -> *const RefCell<Callbacks> {
+    if !libobs::obs_properties_get_param(raw).is_null() {
+        return ptr::null();
+    }
+    let callbacks = Box::into_raw(Box::new(RefCell::new(Callbacks::default())));
+    libobs::obs_properties_set_param(raw, callbacks as *mut c_void, Some(free_callbacks));
+    callbacks
+}
+
+// forgets the properties before they're dropped, even if a callback panics
+struct BorrowedProperties(Properties);
+
+impl Drop for BorrowedProperties {
+    fn drop(&mut self) {
+        self.0.raw.take();
+    }
+}
+
+// lets callbacks use the properties OBS passes in without destroying them
+unsafe fn with_borrowed_properties<F, R>(raw: *mut libobs::obs_properties_t, f: F) -> R
+where
+    F: FnOnce(&mut Properties) -> R,
+{
+    let mut properties = BorrowedProperties(Properties {
+        raw: Some(raw),
+        callbacks: libobs::obs_properties_get_param(raw) as *const RefCell<Callbacks>,
+    });
+    f(&mut properties.0)
+}
+
+// the properties OBS gave the source, which groups were added to
+unsafe fn root_properties(mut raw: *mut libobs::obs_properties_t) -> *mut libobs::obs_properties_t {
+    // older versions have no groups, or a way to find their parent
+    let get_parent = match symbols::get_parent() {
+        Some(get_parent) => get_parent,
+        None => return raw,
+    };
+    loop {
+        let parent = get_parent(raw);
+        if parent.is_null() {
+            return raw;
+        }
+        raw = parent;
+    }
+}
+
+unsafe fn find_callback<T, F>(mut raw: *mut libobs::obs_properties_t, find: F) -> Option<T>
+where
+    F: Fn(&Callbacks) -> Option<T>,
+{
+    let get_parent = symbols::get_parent();
+    loop {
+        let callbacks = libobs::obs_properties_get_param(raw) as *const RefCell<Callbacks>;
+        if !callbacks.is_null() {
+            if let Some(callback) = find(&(*callbacks).borrow()) {
+                return Some(callback);
+            }
+        }
+        raw = match get_parent {
+            Some(get_parent) => get_parent(raw),
+            None => return None,
+        };
+        if raw.is_null() {
+            return None;
+        }
+    }
+}
+
+unsafe fn property_name(property: *mut libobs::obs_property_t) -> String {
+    CStr::from_ptr(libobs::obs_property_name(property))
+        .to_string_lossy()
+        .into_owned()
+}
+
+unsafe extern "C" fn button_clicked(
+    props: *mut libobs::obs_properties_t,
+    property: *mut libobs::obs_property_t,
+    data: *mut c_void,
+) -> bool {
+    catch_panic("button_clicked", || {
+        let name = property_name(property);
+        // clone the callback out so it may add more callbacks while running
+        let callback = find_callback(props, |c| c.buttons.get(&name).cloned());
+        match callback {
+            Some(callback) => {
+                let source = ObsSource::from_source_data(data);
+                with_borrowed_properties(root_properties(props), |props| {
+                    callback(props, source.as_ref())
+                })
+            }
+            None => false,
+        }
+    }).unwrap_or(false)
+}
+
+unsafe extern "C" fn property_modified(
+    props: *mut libobs::obs_properties_t,
+    property: *mut libobs::obs_property_t,
+    settings: *mut libobs::obs_data_t,
+) -> bool {
+    catch_panic("property_modified", || {
+        let name = property_name(property);
+        let callback = find_callback(props, |c| c.modified.get(&name).cloned());
+        match callback {
+            Some(callback) => {
+                // increment because our wrappers are going to decrement on drop
+                libobs::obs_data_addref(settings);
+                let settings = Data::from_raw(settings);
+                with_borrowed_properties(root_properties(props), |props| {
+                    callback(props, &settings)
+                })
+            }
+            None => false,
+        }
+    }).unwrap_or(false)
+}
+
+unsafe extern "C" fn refresh_properties(
+    _props: *mut libobs::obs_properties_t,
+    _property: *mut libobs::obs_property_t,
+    _settings: *mut libobs::obs_data_t,
+) -> bool {
+    true
+}
+
+fn optional_cstring(value: Option<&str>) -> Option<CString> {
+    value.map(|v| CString::new(v).unwrap())
+}
+
+fn optional_ptr(value: &Option<CString>) -> *const c_char {
+    value.as_ref().map(|v| v.as_ptr()).unwrap_or(ptr::null())
+}
+
+pub struct Properties {
+    raw: Option<*mut libobs::obs_properties_t>,
+    // null if the properties can't hold callbacks
+    callbacks: *const RefCell<Callbacks>,
+}
+
+impl Properties {
+    pub fn new() -> Self {
+        unsafe { Properties::from_raw(libobs::obs_properties_create()) }
+    }
+
+    pub(super) unsafe fn from_raw(raw: *mut libobs::obs_properties_t) -> Self {
+        Properties {
+            raw: Some(raw),
+            callbacks: install_callbacks(raw),
+        }
+    }
+
+    pub(super) unsafe fn as_raw(&self) -> *mut libobs::obs_properties_t {
+        self.raw.unwrap()
+    }
+
+    pub(super) unsafe fn into_ptr(mut self) -> *mut libobs::obs_properties_t {
+        self.raw.take().unwrap()
+    }
+
+    fn wrap<'a>(&'a mut self, property: *mut libobs::obs_property_t) -> Property<'a> {
+        Property {
+            property,
+            callbacks: self.callbacks,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn get_property<'a>(&'a mut self, name: &str) -> Option<Property<'a>> {
+        let ptr = unsafe {
+            let name = CString::new(name).unwrap();
+            libobs::obs_properties_get(self.as_raw(), name.as_ptr())
+        };
+        if ptr.is_null() {
+            None
+        } else {
+            Some(self.wrap(ptr))
+        }
+    }
+
+    pub fn add_bool<'a>(&'a mut self, name: &str, description: &str) -> Property<'a> {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let description = CString::new(description).unwrap();
+            let property = libobs::obs_properties_add_bool(
+                self.as_raw(),
+                name.as_ptr(),
+                description.as_ptr(),
+            );
+            self.wrap(property)
+        }
+    }
+
+    pub fn add_int<'a>(
+        &'a mut self,
+        name: &str,
+        description: &str,
+        min: i32,
+        max: i32,
+        step: i32,
+    ) -> Property<'a> {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let description = CString::new(description).unwrap();
+            let property = libobs::obs_properties_add_int(
+                self.as_raw(),
+                name.as_ptr(),
+                description.as_ptr(),
+                min,
+                max,
+                step,
+            );
+            self.wrap(property)
+        }
+    }
+
+    pub fn add_int_slider<'a>(
+        &'a mut self,
+        name: &str,
+        description: &str,
+        min: i32,
+        max: i32,
+        step: i32,
+    ) -> Property<'a> {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let description = CString::new(description).unwrap();
+            let property = libobs::obs_properties_add_int_slider(
+                self.as_raw(),
+                name.as_ptr(),
+                description.as_ptr(),
+                min,
+                max,
+                step,
+            );
+            self.wrap(property)
+        }
+    }
+
+    pub fn add_float<'a>(
+        &'a mut self,
+        name: &str,
+        description: &str,
+        min: f64,
+        max: f64,
+        step: f64,
+    ) -> Property<'a> {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let description = CString::new(description).unwrap();
+            let property = libobs::obs_properties_add_float(
+                self.as_raw(),
+                name.as_ptr(),
+                description.as_ptr(),
+                min,
+                max,
+                step,
+            );
+            self.wrap(property)
+        }
+    }
+
+    pub fn add_float_slider<'a>(
+        &'a mut self,
+        name: &str,
+        description: &str,
+        min: f64,
+        max: f64,
+        step: f64,
+    ) -> Property<'a> {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let description = CString::new(description).unwrap();
+            let property = libobs::obs_properties_add_float_slider(
+                self.as_raw(),
+                name.as_ptr(),
+                description.as_ptr(),
+                min,
+                max,
+                step,
+            );
+            self.wrap(property)
+        }
+    }
+
+    pub fn add_text<'a>(
+        &'a mut self,
+        name: &str,
+        description: &str,
+        text_type: TextType,
+    ) -> Property<'a> {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let description = CString::new(description).unwrap();
+            let property = libobs::obs_properties_add_text(
+                self.as_raw(),
+                name.as_ptr(),
+                description.as_ptr(),
+                text_type.as_raw(),
+            );
+            self.wrap(property)
+        }
+    }
+
+    // `filter` is in the Qt file dialog format, e.g. "Images (*.png *.jpg)"
+    pub fn add_path<'a>(
+        &'a mut self,
+        name: &str,
+        description: &str,
+        path_type: PathType,
+        filter: Option<&str>,
+        default_path: Option<&str>,
+    ) -> Property<'a> {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let description = CString::new(description).unwrap();
+            let filter = optional_cstring(filter);
+            let default_path = optional_cstring(default_path);
+            let property = libobs::obs_properties_add_path(
+                self.as_raw(),
+                name.as_ptr(),
+                description.as_ptr(),
+                path_type.as_raw(),
+                optional_ptr(&filter),
+                optional_ptr(&default_path),
+            );
+            self.wrap(property)
+        }
+    }
+
+    pub fn add_list<'a>(
+        &'a mut self,
+        name: &str,
+        description: &str,
+        combo_type: ComboType,
+        format: ComboFormat,
+    ) -> Property<'a> {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let description = CString::new(description).unwrap();
+            let property = libobs::obs_properties_add_list(
+                self.as_raw(),
+                name.as_ptr(),
+                description.as_ptr(),
+                combo_type.as_raw(),
+                format.as_raw(),
+            );
+            self.wrap(property)
+        }
+    }
+
+    // adds a combo box whose items are strings
+    pub fn add_string_list<'a>(&'a mut self, name: &str, description: &str) -> Property<'a> {
+        self.add_list(name, description, ComboType::List, ComboFormat::String)
+    }
+
+    pub fn add_color<'a>(&'a mut self, name: &str, description: &str) -> Property<'a> {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let description = CString::new(description).unwrap();
+            let property = libobs::obs_properties_add_color(
+                self.as_raw(),
+                name.as_ptr(),
+                description.as_ptr(),
+            );
+            self.wrap(property)
+        }
+    }
+
+    pub fn add_font<'a>(&'a mut self, name: &str, description: &str) -> Property<'a> {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let description = CString::new(description).unwrap();
+            let property = libobs::obs_properties_add_font(
+                self.as_raw(),
+                name.as_ptr(),
+                description.as_ptr(),
+            );
+            self.wrap(property)
+        }
+    }
+
+    // `callback` is given the source whose properties these are, which is None
+    // for the properties of a source type, and returns true if the properties
+    // view should be refreshed
+    pub fn add_button<'a, F>(&'a mut self, name: &str, text: &str, callback: F) -> Property<'a>
+    where
+        F: Fn(&mut Properties, Option<&ObsSource>) -> bool + 'static,
+    {
+        unsafe {
+            let clicked = if self.callbacks.is_null() {
+                error!("cannot add a callback for button {:?}", name);
+                None
+            } else {
+                (*self.callbacks)
+                    .borrow_mut()
+                    .buttons
+                    .insert(name.to_string(), Rc::new(callback));
+                Some(button_clicked as unsafe extern "C" fn(_, _, _) -> _)
+            };
+            let name = CString::new(name).unwrap();
+            let text = CString::new(text).unwrap();
+            let property = libobs::obs_properties_add_button(
+                self.as_raw(),
+                name.as_ptr(),
+                text.as_ptr(),
+                clicked,
+            );
+            self.wrap(property)
+        }
+    }
+
+    pub fn add_editable_list<'a>(
+        &'a mut self,
+        name: &str,
+        description: &str,
+        list_type: EditableListType,
+        filter: Option<&str>,
+        default_path: Option<&str>,
+    ) -> Property<'a> {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let description = CString::new(description).unwrap();
+            let filter = optional_cstring(filter);
+            let default_path = optional_cstring(default_path);
+            let property = libobs::obs_properties_add_editable_list(
+                self.as_raw(),
+                name.as_ptr(),
+                description.as_ptr(),
+                list_type.as_raw(),
+                optional_ptr(&filter),
+                optional_ptr(&default_path),
+            );
+            self.wrap(property)
+        }
+    }
+
+    // the group takes ownership of `group`. returns None if `group` could not
+    // be added, which happens if it has a property with the same name as one
+    // of ours, or if OBS is older than 22, which introduced groups.
+    pub fn add_group<'a>(
+        &'a mut self,
+        name: &str,
+        description: &str,
+        group_type: GroupType,
+        group: Properties,
+    ) -> Option<Property<'a>> {
+        let add_group = match symbols::add_group() {
+            Some(add_group) => add_group,
+            None => {
+                let version = runtime_version();
+                warn!("cannot add group {:?} because OBS {} has no groups", name, version);
+                return None;
+            }
+        };
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let description = CString::new(description).unwrap();
+            let group = group.into_ptr();
+            let property = add_group(
+                self.as_raw(),
+                name.as_ptr(),
+                description.as_ptr(),
+                group_type.as_raw(),
+                group,
+            );
+            if property.is_null() {
+                libobs::obs_properties_destroy(group);
+                None
+            } else {
+                Some(self.wrap(property))
+            }
+        }
+    }
+}
+
+impl Drop for Properties {
+    fn drop(&mut self) {
+        unsafe {
+            if let Some(properties) = self.raw.take() {
+                libobs::obs_properties_destroy(properties);
+            }
+        }
+    }
+}
+
+pub struct Property<'a> {
+    property: *mut libobs::obs_property_t,
+    callbacks: *const RefCell<Callbacks>,
+    marker: PhantomData<&'a ()>,
+}
+
+impl<'a> Property<'a> {
+    pub fn get_name(&self) -> String {
+        unsafe { property_name(self.property) }
+    }
+    pub fn set_visible(&mut self, visibility: bool) {
+        unsafe {
+            libobs::obs_property_set_visible(self.property, visibility);
+        }
+    }
+    pub fn set_enabled(&mut self, enabled: bool) {
+        unsafe {
+            libobs::obs_property_set_enabled(self.property, enabled);
+        }
+    }
+    pub fn set_description(&mut self, description: &str) {
+        unsafe {
+            let description = CString::new(description).unwrap();
+            libobs::obs_property_set_description(self.property, description.as_ptr())
+        }
+    }
+    // shown as a tooltip
+    pub fn set_long_description(&mut self, description: &str) {
+        unsafe {
+            let description = CString::new(description).unwrap();
+            libobs::obs_property_set_long_description(self.property, description.as_ptr())
+        }
+    }
+    // reload the properties view whenever this property changes
+    pub fn set_refresh_on_change(&mut self) {
+        unsafe {
+            libobs::obs_property_set_modified_callback(self.property, Some(refresh_properties));
+        }
+    }
+    // `callback` is called with the new settings when the property changes
+    // and returns true if the properties view should be refreshed. this
+    // replaces any callback the property already had.
+    pub fn set_modified_callback<F>(&mut self, callback: F)
+    where
+        F: Fn(&mut Properties, &Data) -> bool + 'static,
+    {
+        unsafe {
+            if self.callbacks.is_null() {
+                error!("cannot add a modified callback for {:?}", self.get_name());
+                return;
+            }
+            (*self.callbacks)
+                .borrow_mut()
+                .modified
+                .insert(self.get_name(), Rc::new(callback));
+            libobs::obs_property_set_modified_callback(self.property, Some(property_modified));
+        }
+    }
+    pub fn add_string_item(&mut self, name: &str, value: &str) {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let value = CString::new(value).unwrap();
+            libobs::obs_property_list_add_string(self.property, name.as_ptr(), value.as_ptr());
+        }
+    }
+    pub fn add_int_item(&mut self, name: &str, value: i64) {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            libobs::obs_property_list_add_int(self.property, name.as_ptr(), value);
+        }
+    }
+    pub fn add_float_item(&mut self, name: &str, value: f64) {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            libobs::obs_property_list_add_float(self.property, name.as_ptr(), value);
+        }
+    }
+    pub fn clear_items(&mut self) {
+        unsafe {
+            libobs::obs_property_list_clear(self.property);
+        }
+    }
+}
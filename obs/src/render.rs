@@ -0,0 +1,65 @@
+use std::cell::{Ref, RefCell, RefMut};
+use std::marker::PhantomData;
+use libobs;
+
+// proof that the current thread is inside the graphics context, either because
+// OBS called us to render or because of `enter_graphics`. only one thread can
+// be in the graphics context at a time, so holding one of these is as good as
+// holding a lock. it can't be created outside this crate and it can't be
+// sent to another thread, so it's only ever handed out by reference.
+pub struct RenderToken {
+    _not_send: PhantomData<*mut ()>,
+}
+
+impl RenderToken {
+    pub(super) unsafe fn new() -> Self {
+        check_graphics_thread();
+        RenderToken {
+            _not_send: PhantomData,
+        }
+    }
+}
+
+#[cfg(debug_assertions)]
+fn check_graphics_thread() {
+    unsafe {
+        assert!(
+            !libobs::gs_get_context().is_null(),
+            "render thread data accessed outside the graphics context"
+        );
+    }
+}
+
+#[cfg(not(debug_assertions))]
+fn check_graphics_thread() {}
+
+// data that can be shared with other threads but only used from inside the
+// graphics context. unlike a Mutex there's nothing to lock on the render
+// thread, which matters for things touched several times per frame.
+pub struct RenderCell<T> {
+    value: RefCell<T>,
+}
+
+impl<T> RenderCell<T> {
+    pub fn new(value: T) -> Self {
+        RenderCell {
+            value: RefCell::new(value),
+        }
+    }
+    pub fn borrow<'a>(&'a self, _token: &'a RenderToken) -> Ref<'a, T> {
+        check_graphics_thread();
+        self.value.borrow()
+    }
+    pub fn borrow_mut<'a>(&'a self, _token: &'a RenderToken) -> RefMut<'a, T> {
+        check_graphics_thread();
+        self.value.borrow_mut()
+    }
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+// the RefCell's borrow flag is only touched by whoever holds a RenderToken,
+// and the graphics context makes sure that's one thread at a time
+unsafe impl<T: Send> Send for RenderCell<T> {}
+unsafe impl<T: Send> Sync for RenderCell<T> {}
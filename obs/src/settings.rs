@@ -0,0 +1,171 @@
+use std::{f64, i32};
+use super::{Data, Properties, TextType};
+
+// hints for the property shown for a value. which ones apply depends on the
+// type of the value.
+#[derive(Clone, Debug, Default)]
+pub struct PropertyOptions {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub step: Option<f64>,
+    pub slider: bool,
+    pub multiline: bool,
+}
+
+// something that can be stored under a single key. enums can use
+// #[derive(SettingsValue)].
+pub trait SettingsValue: Sized {
+    fn get_value(data: &Data, key: &str) -> Self;
+    fn set_value(&self, data: &mut Data, key: &str);
+    fn set_default_value(&self, data: &mut Data, key: &str);
+    fn add_property(props: &mut Properties, key: &str, label: &str, options: &PropertyOptions);
+}
+
+// a whole set of source settings. use #[derive(Settings)].
+pub trait Settings: Sized {
+    fn from_data(data: &Data) -> Self;
+    fn to_data(&self, data: &mut Data);
+    fn set_defaults(data: &mut Data);
+    fn add_properties(props: &mut Properties);
+    fn properties() -> Properties {
+        let mut props = Properties::new();
+        Self::add_properties(&mut props);
+        props
+    }
+}
+
+impl SettingsValue for String {
+    fn get_value(data: &Data, key: &str) -> Self {
+        data.get_string(key).unwrap_or_default()
+    }
+    fn set_value(&self, data: &mut Data, key: &str) {
+        data.set_string(key, self);
+    }
+    fn set_default_value(&self, data: &mut Data, key: &str) {
+        data.set_default_string(key, self);
+    }
+    fn add_property(props: &mut Properties, key: &str, label: &str, options: &PropertyOptions) {
+        let text_type = if options.multiline {
+            TextType::Multiline
+        } else {
+            TextType::Default
+        };
+        props.add_text(key, label, text_type);
+    }
+}
+
+impl SettingsValue for bool {
+    fn get_value(data: &Data, key: &str) -> Self {
+        data.get_bool(key)
+    }
+    fn set_value(&self, data: &mut Data, key: &str) {
+        data.set_bool(key, *self);
+    }
+    fn set_default_value(&self, data: &mut Data, key: &str) {
+        data.set_default_bool(key, *self);
+    }
+    fn add_property(props: &mut Properties, key: &str, label: &str, _options: &PropertyOptions) {
+        props.add_bool(key, label);
+    }
+}
+
+fn add_int_property(props: &mut Properties, key: &str, label: &str, options: &PropertyOptions) {
+    let min = options.min.map(|v| v as i32).unwrap_or(i32::MIN);
+    let max = options.max.map(|v| v as i32).unwrap_or(i32::MAX);
+    let step = options.step.map(|v| v as i32).unwrap_or(1);
+    if options.slider {
+        props.add_int_slider(key, label, min, max, step);
+    } else {
+        props.add_int(key, label, min, max, step);
+    }
+}
+
+fn add_float_property(props: &mut Properties, key: &str, label: &str, options: &PropertyOptions) {
+    let min = options.min.unwrap_or(f64::MIN);
+    let max = options.max.unwrap_or(f64::MAX);
+    let step = options.step.unwrap_or(0.1);
+    if options.slider {
+        props.add_float_slider(key, label, min, max, step);
+    } else {
+        props.add_float(key, label, min, max, step);
+    }
+}
+
+impl SettingsValue for i64 {
+    fn get_value(data: &Data, key: &str) -> Self {
+        data.get_int(key)
+    }
+    fn set_value(&self, data: &mut Data, key: &str) {
+        data.set_int(key, *self);
+    }
+    fn set_default_value(&self, data: &mut Data, key: &str) {
+        data.set_default_int(key, *self);
+    }
+    fn add_property(props: &mut Properties, key: &str, label: &str, options: &PropertyOptions) {
+        add_int_property(props, key, label, options);
+    }
+}
+
+impl SettingsValue for i32 {
+    fn get_value(data: &Data, key: &str) -> Self {
+        data.get_int(key) as i32
+    }
+    fn set_value(&self, data: &mut Data, key: &str) {
+        data.set_int(key, i64::from(*self));
+    }
+    fn set_default_value(&self, data: &mut Data, key: &str) {
+        data.set_default_int(key, i64::from(*self));
+    }
+    fn add_property(props: &mut Properties, key: &str, label: &str, options: &PropertyOptions) {
+        add_int_property(props, key, label, options);
+    }
+}
+
+impl SettingsValue for u32 {
+    fn get_value(data: &Data, key: &str) -> Self {
+        data.get_int(key).max(0) as u32
+    }
+    fn set_value(&self, data: &mut Data, key: &str) {
+        data.set_int(key, i64::from(*self));
+    }
+    fn set_default_value(&self, data: &mut Data, key: &str) {
+        data.set_default_int(key, i64::from(*self));
+    }
+    fn add_property(props: &mut Properties, key: &str, label: &str, options: &PropertyOptions) {
+        let options = PropertyOptions {
+            min: Some(options.min.unwrap_or(0.0).max(0.0)),
+            ..options.clone()
+        };
+        add_int_property(props, key, label, &options);
+    }
+}
+
+impl SettingsValue for f64 {
+    fn get_value(data: &Data, key: &str) -> Self {
+        data.get_double(key)
+    }
+    fn set_value(&self, data: &mut Data, key: &str) {
+        data.set_double(key, *self);
+    }
+    fn set_default_value(&self, data: &mut Data, key: &str) {
+        data.set_default_double(key, *self);
+    }
+    fn add_property(props: &mut Properties, key: &str, label: &str, options: &PropertyOptions) {
+        add_float_property(props, key, label, options);
+    }
+}
+
+impl SettingsValue for f32 {
+    fn get_value(data: &Data, key: &str) -> Self {
+        data.get_double(key) as f32
+    }
+    fn set_value(&self, data: &mut Data, key: &str) {
+        data.set_double(key, f64::from(*self));
+    }
+    fn set_default_value(&self, data: &mut Data, key: &str) {
+        data.set_default_double(key, f64::from(*self));
+    }
+    fn add_property(props: &mut Properties, key: &str, label: &str, options: &PropertyOptions) {
+        add_float_property(props, key, label, options);
+    }
+}
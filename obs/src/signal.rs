@@ -0,0 +1,310 @@
+use std::ffi::{CStr, CString};
+use std::mem;
+use std::os::raw::{c_char, c_void};
+use std::ptr;
+use libobs;
+use super::{catch_panic, ObsSource, ObsWeakSource};
+
+// the parameters and return values of signals and procs. the layout has to
+// match calldata_t because OBS gives us pointers to its own.
+#[repr(C)]
+pub struct CallData(libobs::calldata_t);
+
+impl CallData {
+    pub fn new() -> Self {
+        unsafe { CallData(mem::zeroed()) }
+    }
+
+    unsafe fn from_ptr<'a>(raw: *mut libobs::calldata_t) -> &'a mut Self {
+        &mut *(raw as *mut CallData)
+    }
+
+    fn as_ptr(&mut self) -> *mut libobs::calldata_t {
+        &mut self.0
+    }
+
+    // calldata_set_int and friends are inline, so we do what they do
+    fn set_data<T: Copy>(&mut self, name: &str, value: T) {
+        let name = CString::new(name).unwrap();
+        unsafe {
+            libobs::calldata_set_data(
+                self.as_ptr(),
+                name.as_ptr(),
+                &value as *const T as *const c_void,
+                mem::size_of::<T>(),
+            );
+        }
+    }
+
+    fn get_data<T: Copy>(&self, name: &str) -> Option<T> {
+        let name = CString::new(name).unwrap();
+        unsafe {
+            let mut value: T = mem::zeroed();
+            if libobs::calldata_get_data(
+                &self.0,
+                name.as_ptr(),
+                &mut value as *mut T as *mut c_void,
+                mem::size_of::<T>(),
+            ) {
+                Some(value)
+            } else {
+                None
+            }
+        }
+    }
+
+    pub fn set_int(&mut self, name: &str, value: i64) {
+        self.set_data(name, value);
+    }
+
+    pub fn get_int(&self, name: &str) -> Option<i64> {
+        self.get_data(name)
+    }
+
+    pub fn set_float(&mut self, name: &str, value: f64) {
+        self.set_data(name, value);
+    }
+
+    pub fn get_float(&self, name: &str) -> Option<f64> {
+        self.get_data(name)
+    }
+
+    pub fn set_bool(&mut self, name: &str, value: bool) {
+        self.set_data(name, value);
+    }
+
+    pub fn get_bool(&self, name: &str) -> Option<bool> {
+        self.get_data(name)
+    }
+
+    pub fn set_string(&mut self, name: &str, value: &str) {
+        let name = CString::new(name).unwrap();
+        let value = CString::new(value).unwrap();
+        unsafe {
+            libobs::calldata_set_string(self.as_ptr(), name.as_ptr(), value.as_ptr());
+        }
+    }
+
+    pub fn get_string(&self, name: &str) -> Option<String> {
+        let name = CString::new(name).unwrap();
+        unsafe {
+            let mut value: *const c_char = ptr::null();
+            if libobs::calldata_get_string(&self.0, name.as_ptr(), &mut value) && !value.is_null()
+            {
+                Some(CStr::from_ptr(value).to_string_lossy().into_owned())
+            } else {
+                None
+            }
+        }
+    }
+
+    // for the `ptr source` parameter of source signals
+    pub fn get_source(&self, name: &str) -> Option<ObsSource> {
+        self.get_data::<*mut libobs::obs_source>(name)
+            .and_then(|source| unsafe { ObsSource::from_unowned(source) })
+    }
+
+    pub fn set_source(&mut self, name: &str, source: &ObsSource) {
+        self.set_data(name, unsafe { source.as_raw() });
+    }
+}
+
+impl Default for CallData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for CallData {
+    fn drop(&mut self) {
+        // calldata_free is inline too
+        if !self.0.fixed && !self.0.stack.is_null() {
+            unsafe {
+                libobs::bfree(self.0.stack as *mut c_void);
+            }
+        }
+    }
+}
+
+type SignalCallback = Box<Fn(&mut CallData) + Send + Sync>;
+
+unsafe extern "C" fn signal_callback(data: *mut c_void, cd: *mut libobs::calldata_t) {
+    catch_panic("signal_callback", || {
+        let callback = &*(data as *mut SignalCallback);
+        callback(CallData::from_ptr(cd));
+    });
+}
+
+// per-source handlers are destroyed with their source. holding the source
+// itself would keep it alive for as long as anything is connected, which is
+// forever when the source keeps its own connections, so the handlers hold a
+// weak reference and do nothing once the source is gone.
+#[derive(Clone)]
+struct Owner(Option<ObsWeakSource>);
+
+impl Owner {
+    // runs `f` if the handler still exists, keeping it alive until `f` is done
+    fn with_handler<F, R>(&self, f: F) -> Option<R>
+    where
+        F: FnOnce() -> R,
+    {
+        match self.0 {
+            Some(ref source) => source.upgrade().map(|_source| f()),
+            None => Some(f()),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SignalHandler {
+    raw: *mut libobs::signal_handler_t,
+    owner: Owner,
+}
+
+impl SignalHandler {
+    pub(super) unsafe fn from_raw(
+        raw: *mut libobs::signal_handler_t,
+        source: Option<ObsWeakSource>,
+    ) -> Self {
+        SignalHandler {
+            raw,
+            owner: Owner(source),
+        }
+    }
+
+    // `decl` looks like "void track_changed(string title)"
+    pub fn add_signal(&self, decl: &str) -> bool {
+        let decl = CString::new(decl).unwrap();
+        self.owner
+            .with_handler(|| unsafe { libobs::signal_handler_add(self.raw, decl.as_ptr()) })
+            .unwrap_or(false)
+    }
+
+    pub fn signal(&self, name: &str, data: &mut CallData) {
+        let name = CString::new(name).unwrap();
+        self.owner.with_handler(|| unsafe {
+            libobs::signal_handler_signal(self.raw, name.as_ptr(), data.as_ptr());
+        });
+    }
+
+    // `callback` runs on whichever thread sends the signal until the
+    // connection is dropped
+    pub fn connect<F>(&self, name: &str, callback: F) -> SignalConnection
+    where
+        F: Fn(&mut CallData) + Send + Sync + 'static,
+    {
+        let callback: SignalCallback = Box::new(callback);
+        let connection = SignalConnection {
+            handler: self.clone(),
+            name: CString::new(name).unwrap(),
+            callback: Box::into_raw(Box::new(callback)),
+        };
+        self.owner.with_handler(|| unsafe {
+            libobs::signal_handler_connect(
+                self.raw,
+                connection.name.as_ptr(),
+                Some(signal_callback),
+                connection.callback as *mut c_void,
+            );
+        });
+        connection
+    }
+}
+
+unsafe impl Send for SignalHandler {}
+unsafe impl Sync for SignalHandler {}
+
+pub struct SignalConnection {
+    handler: SignalHandler,
+    name: CString,
+    callback: *mut SignalCallback,
+}
+
+impl Drop for SignalConnection {
+    fn drop(&mut self) {
+        // once the source is being destroyed it sends no more signals, so
+        // there's nothing to disconnect from
+        let handler = &self.handler;
+        handler.owner.with_handler(|| unsafe {
+            libobs::signal_handler_disconnect(
+                handler.raw,
+                self.name.as_ptr(),
+                Some(signal_callback),
+                self.callback as *mut c_void,
+            );
+        });
+        unsafe {
+            mem::drop(Box::from_raw(self.callback));
+        }
+    }
+}
+
+unsafe impl Send for SignalConnection {}
+unsafe impl Sync for SignalConnection {}
+
+type ProcCallback = Box<Fn(&mut CallData) + Send + Sync>;
+
+unsafe extern "C" fn proc_callback(data: *mut c_void, cd: *mut libobs::calldata_t) {
+    catch_panic("proc_callback", || {
+        let callback = &*(data as *mut ProcCallback);
+        callback(CallData::from_ptr(cd));
+    });
+}
+
+#[derive(Clone)]
+pub struct ProcHandler {
+    raw: *mut libobs::proc_handler_t,
+    owner: Owner,
+}
+
+impl ProcHandler {
+    pub(super) unsafe fn from_raw(
+        raw: *mut libobs::proc_handler_t,
+        source: Option<ObsWeakSource>,
+    ) -> Self {
+        ProcHandler {
+            raw,
+            owner: Owner(source),
+        }
+    }
+
+    // `decl` looks like "void get_track(out string title)". OBS has no way of
+    // removing procs, so `callback` is kept until the handler is destroyed,
+    // which for the global handler is never.
+    pub fn add<F>(&self, decl: &str, callback: F)
+    where
+        F: Fn(&mut CallData) + Send + Sync + 'static,
+    {
+        let decl = CString::new(decl).unwrap();
+        let callback: ProcCallback = Box::new(callback);
+        self.owner.with_handler(|| unsafe {
+            libobs::proc_handler_add(
+                self.raw,
+                decl.as_ptr(),
+                Some(proc_callback),
+                Box::into_raw(Box::new(callback)) as *mut c_void,
+            );
+        });
+    }
+
+    // false if the proc failed or doesn't exist, or if the source is gone
+    pub fn call(&self, name: &str, data: &mut CallData) -> bool {
+        let name = CString::new(name).unwrap();
+        self.owner
+            .with_handler(|| unsafe {
+                libobs::proc_handler_call(self.raw, name.as_ptr(), data.as_ptr())
+            })
+            .unwrap_or(false)
+    }
+}
+
+unsafe impl Send for ProcHandler {}
+unsafe impl Sync for ProcHandler {}
+
+pub fn get_signal_handler() -> SignalHandler {
+    unsafe { SignalHandler::from_raw(libobs::obs_get_signal_handler(), None) }
+}
+
+pub fn get_proc_handler() -> ProcHandler {
+    unsafe { ProcHandler::from_raw(libobs::obs_get_proc_handler(), None) }
+}
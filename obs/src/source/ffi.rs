@@ -0,0 +1,647 @@
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::{mem, ptr};
+use std::os::raw::{c_char, c_void};
+use std::sync::Mutex;
+use libobs;
+use super::super::catch_panic;
+use super::{AudioSource, AudioSourceDefinition, Data, FilterAudio, FilterSource,
+            FilterSourceDefinition, KeyEvent, MouseButton, MouseEvent, ObsFilter, ObsSource,
+            Properties, RenderToken, VideoSource, VideoSourceDefinition};
+
+// given to OBS if get_name panics
+const EMPTY_NAME: &[u8] = b"\0";
+
+pub(super) struct SourceDefinition<D> {
+    pub id: CString,
+    pub name: CString,
+    pub inner: D,
+}
+
+// what we give OBS as the data for each source, so that callbacks OBS doesn't
+// pass the obs_source to can still get at it. not addref'd because the source
+// owns us. repr(C) with the source first so it can be found without knowing S.
+#[repr(C)]
+pub(super) struct SourceData<S> {
+    source: *mut libobs::obs_source,
+    inner: S,
+}
+
+// for callbacks that get our data without its type, like button callbacks
+pub(super) unsafe fn source_of_data(data: *mut c_void) -> *mut libobs::obs_source {
+    (*(data as *mut SourceData<()>)).source
+}
+
+pub(super) unsafe extern "C" fn source_get_name<D>(data: *mut c_void) -> *const c_char {
+    catch_panic("source_get_name", || {
+        let data = &*(data as *mut SourceDefinition<D>);
+        data.name.as_ptr()
+    }).unwrap_or(EMPTY_NAME.as_ptr() as *const c_char)
+}
+
+lazy_static! {
+    // the type_data of each source type, by id. OBS doesn't pass it to
+    // create and the obs_source it's kept in is private.
+    static ref DEFINITIONS: Mutex<HashMap<CString, usize>> = Mutex::new(HashMap::new());
+}
+
+// called with the type_data before it's given to OBS
+pub(super) fn add_definition<D>(definition: *mut SourceDefinition<D>) {
+    let id = unsafe { (*definition).id.clone() };
+    // if `id` is taken OBS will refuse to register this one and free it
+    DEFINITIONS
+        .lock()
+        .unwrap()
+        .entry(id)
+        .or_insert(definition as usize);
+}
+
+unsafe fn get_definition<'a, D>(source: *mut libobs::obs_source) -> &'a SourceDefinition<D> {
+    let id = CStr::from_ptr(libobs::obs_source_get_id(source));
+    let definition = *DEFINITIONS
+        .lock()
+        .unwrap()
+        .get(id)
+        .expect("creating a source of a type that wasn't registered");
+    &*(definition as *mut SourceDefinition<D>)
+}
+
+pub(super) unsafe extern "C" fn source_free_type_data<D>(data: *mut c_void) {
+    catch_panic("source_free_type_data", || {
+        let definition = Box::from_raw(data as *mut SourceDefinition<D>);
+        let mut definitions = DEFINITIONS.lock().unwrap();
+        if definitions.get(&definition.id) == Some(&(data as usize)) {
+            definitions.remove(&definition.id);
+        }
+    });
+}
+
+// the parts of creating a source that are the same for every kind of
+// source. `create` gets the definition and the settings.
+unsafe fn create_source<D, S, F>(
+    context: &str,
+    settings: *mut libobs::obs_data,
+    source: *mut libobs::obs_source,
+    create: F,
+) -> *mut c_void
+where
+    F: FnOnce(&D, &Data) -> S,
+{
+    catch_panic(context, || {
+        let data = get_definition::<D>(source);
+
+        // increment because our wrappers are going to decrement on drop
+        libobs::obs_data_addref(settings);
+        let inner = create(&data.inner, &Data::from_raw(settings));
+        Box::into_raw(Box::new(SourceData { source, inner })) as *mut c_void
+    }).unwrap_or(ptr::null_mut())
+}
+
+unsafe fn get_source_defaults<D, F>(
+    context: &str,
+    data: *mut c_void,
+    settings: *mut libobs::obs_data,
+    get_defaults: F,
+) where
+    F: FnOnce(&D, &mut Data),
+{
+    catch_panic(context, || {
+        let data = &*(data as *mut SourceDefinition<D>);
+        // increment because our wrappers are going to decrement on drop
+        libobs::obs_data_addref(settings);
+        get_defaults(&data.inner, &mut Data::from_raw(settings));
+    });
+}
+
+unsafe fn get_source_properties<S, F>(
+    context: &str,
+    source: *mut c_void,
+    get_properties: F,
+) -> *mut libobs::obs_properties_t
+where
+    F: FnOnce(&S) -> Properties,
+{
+    catch_panic(context, || {
+        // OBS passes null when asking for the properties of the type rather
+        // than of a specific source
+        if source.is_null() {
+            return Properties::new().into_ptr();
+        }
+        let source = &(*(source as *mut SourceData<S>)).inner;
+        get_properties(source).into_ptr()
+    }).unwrap_or(ptr::null_mut())
+}
+
+unsafe fn update_source<S, F>(
+    context: &str,
+    source: *mut c_void,
+    settings: *mut libobs::obs_data,
+    update: F,
+) where
+    F: FnOnce(&mut S, &Data),
+{
+    catch_panic(context, || {
+        let source = &mut (*(source as *mut SourceData<S>)).inner;
+        // increment because our wrappers are going to decrement on drop
+        libobs::obs_data_addref(settings);
+        update(source, &Data::from_raw(settings));
+    });
+}
+
+// for callbacks that only need the source
+unsafe fn with_source<S, F>(context: &str, source: *mut c_void, f: F)
+where
+    F: FnOnce(&mut S),
+{
+    catch_panic(context, || {
+        f(&mut (*(source as *mut SourceData<S>)).inner);
+    });
+}
+
+unsafe fn save_source<S, F>(
+    context: &str,
+    source: *mut c_void,
+    settings: *mut libobs::obs_data,
+    save: F,
+) where
+    F: FnOnce(&mut S, &mut Data),
+{
+    catch_panic(context, || {
+        let source = &mut (*(source as *mut SourceData<S>)).inner;
+        // increment because our wrappers are going to decrement on drop
+        libobs::obs_data_addref(settings);
+        save(source, &mut Data::from_raw(settings));
+    });
+}
+
+pub(super) unsafe extern "C" fn source_create<D>(
+    settings: *mut libobs::obs_data,
+    source: *mut libobs::obs_source,
+) -> *mut c_void
+where
+    D: VideoSourceDefinition,
+{
+    create_source::<D, _, _>("source_create", settings, source, |definition, settings| {
+        libobs::obs_source_addref(source);
+        definition.create(settings, &mut ObsSource(source))
+    })
+}
+
+pub(super) unsafe extern "C" fn source_destroy<S>(source: *mut c_void) {
+    catch_panic("source_destroy", || {
+        mem::drop(Box::from_raw(source as *mut SourceData<S>))
+    });
+}
+
+pub(super) unsafe extern "C" fn source_get_defaults<D>(
+    data: *mut c_void,
+    settings: *mut libobs::obs_data,
+) where
+    D: VideoSourceDefinition,
+{
+    get_source_defaults::<D, _>("source_get_defaults", data, settings, |definition, settings| {
+        definition.get_defaults(settings)
+    });
+}
+
+pub(super) unsafe extern "C" fn source_get_properties<S>(
+    source: *mut c_void,
+) -> *mut libobs::obs_properties_t
+where
+    S: VideoSource,
+{
+    get_source_properties::<S, _>("source_get_properties", source, |source| {
+        source.get_properties()
+    })
+}
+
+pub(super) unsafe extern "C" fn source_update<S>(
+    source: *mut c_void,
+    settings: *mut libobs::obs_data,
+) where
+    S: VideoSource,
+{
+    update_source::<S, _>("source_update", source, settings, |source, settings| {
+        source.update(settings)
+    });
+}
+
+pub(super) unsafe extern "C" fn source_get_width<S>(source: *mut c_void) -> u32
+where
+    S: VideoSource,
+{
+    catch_panic("source_get_width", || {
+        let source = &mut (*(source as *mut SourceData<S>)).inner;
+        source.get_width()
+    }).unwrap_or(0)
+}
+
+pub(super) unsafe extern "C" fn source_get_height<S>(source: *mut c_void) -> u32
+where
+    S: VideoSource,
+{
+    catch_panic("source_get_height", || {
+        let source = &mut (*(source as *mut SourceData<S>)).inner;
+        source.get_height()
+    }).unwrap_or(0)
+}
+
+pub(super) unsafe extern "C" fn source_video_tick<S>(source: *mut c_void, seconds: f32)
+where
+    S: VideoSource,
+{
+    catch_panic("source_video_tick", || {
+        let source = &mut (*(source as *mut SourceData<S>)).inner;
+        source.video_tick(seconds);
+    });
+}
+
+pub(super) unsafe extern "C" fn source_video_render<S>(
+    source: *mut c_void,
+    _effect: *mut libobs::gs_effect,
+) where
+    S: VideoSource,
+{
+    catch_panic("source_video_render", || {
+        let source = &mut (*(source as *mut SourceData<S>)).inner;
+        source.video_render(&RenderToken::new());
+    });
+}
+
+pub(super) unsafe extern "C" fn audio_source_create<D>(
+    settings: *mut libobs::obs_data,
+    source: *mut libobs::obs_source,
+) -> *mut c_void
+where
+    D: AudioSourceDefinition,
+{
+    create_source::<D, _, _>("audio_source_create", settings, source, |definition, settings| {
+        libobs::obs_source_addref(source);
+        definition.create(settings, &mut ObsSource(source))
+    })
+}
+
+pub(super) unsafe extern "C" fn audio_source_get_defaults<D>(
+    data: *mut c_void,
+    settings: *mut libobs::obs_data,
+) where
+    D: AudioSourceDefinition,
+{
+    get_source_defaults::<D, _>(
+        "audio_source_get_defaults",
+        data,
+        settings,
+        |definition, settings| definition.get_defaults(settings),
+    );
+}
+
+pub(super) unsafe extern "C" fn audio_source_get_properties<S>(
+    source: *mut c_void,
+) -> *mut libobs::obs_properties_t
+where
+    S: AudioSource,
+{
+    get_source_properties::<S, _>("audio_source_get_properties", source, |source| {
+        source.get_properties()
+    })
+}
+
+pub(super) unsafe extern "C" fn audio_source_update<S>(
+    source: *mut c_void,
+    settings: *mut libobs::obs_data,
+) where
+    S: AudioSource,
+{
+    update_source::<S, _>("audio_source_update", source, settings, |source, settings| {
+        source.update(settings)
+    });
+}
+
+pub(super) unsafe extern "C" fn audio_source_activate<S>(source: *mut c_void)
+where
+    S: AudioSource,
+{
+    with_source::<S, _>("audio_source_activate", source, |source| source.activate());
+}
+
+pub(super) unsafe extern "C" fn audio_source_deactivate<S>(source: *mut c_void)
+where
+    S: AudioSource,
+{
+    with_source::<S, _>("audio_source_deactivate", source, |source| source.deactivate());
+}
+
+pub(super) unsafe extern "C" fn audio_source_show<S>(source: *mut c_void)
+where
+    S: AudioSource,
+{
+    with_source::<S, _>("audio_source_show", source, |source| source.show());
+}
+
+pub(super) unsafe extern "C" fn audio_source_hide<S>(source: *mut c_void)
+where
+    S: AudioSource,
+{
+    with_source::<S, _>("audio_source_hide", source, |source| source.hide());
+}
+
+pub(super) unsafe extern "C" fn audio_source_save<S>(
+    source: *mut c_void,
+    settings: *mut libobs::obs_data,
+) where
+    S: AudioSource,
+{
+    save_source::<S, _>("audio_source_save", source, settings, |source, settings| {
+        source.save(settings)
+    });
+}
+
+pub(super) unsafe extern "C" fn audio_source_load<S>(
+    source: *mut c_void,
+    settings: *mut libobs::obs_data,
+) where
+    S: AudioSource,
+{
+    update_source::<S, _>("audio_source_load", source, settings, |source, settings| {
+        source.load(settings)
+    });
+}
+
+pub(super) unsafe extern "C" fn filter_create<D>(
+    settings: *mut libobs::obs_data,
+    source: *mut libobs::obs_source,
+) -> *mut c_void
+where
+    D: FilterSourceDefinition,
+{
+    // the filter isn't addref'd because it owns us
+    create_source::<D, _, _>("filter_create", settings, source, |definition, settings| {
+        definition.create(settings, &ObsFilter(source))
+    })
+}
+
+pub(super) unsafe extern "C" fn filter_get_defaults<D>(
+    data: *mut c_void,
+    settings: *mut libobs::obs_data,
+) where
+    D: FilterSourceDefinition,
+{
+    get_source_defaults::<D, _>("filter_get_defaults", data, settings, |definition, settings| {
+        definition.get_defaults(settings)
+    });
+}
+
+pub(super) unsafe extern "C" fn filter_get_properties<S>(
+    source: *mut c_void,
+) -> *mut libobs::obs_properties_t
+where
+    S: FilterSource,
+{
+    get_source_properties::<S, _>("filter_get_properties", source, |source| {
+        source.get_properties()
+    })
+}
+
+pub(super) unsafe extern "C" fn filter_update<S>(
+    source: *mut c_void,
+    settings: *mut libobs::obs_data,
+) where
+    S: FilterSource,
+{
+    update_source::<S, _>("filter_update", source, settings, |source, settings| {
+        source.update(settings)
+    });
+}
+
+pub(super) unsafe extern "C" fn filter_activate<S>(source: *mut c_void)
+where
+    S: FilterSource,
+{
+    with_source::<S, _>("filter_activate", source, |source| source.activate());
+}
+
+pub(super) unsafe extern "C" fn filter_deactivate<S>(source: *mut c_void)
+where
+    S: FilterSource,
+{
+    with_source::<S, _>("filter_deactivate", source, |source| source.deactivate());
+}
+
+pub(super) unsafe extern "C" fn filter_show<S>(source: *mut c_void)
+where
+    S: FilterSource,
+{
+    with_source::<S, _>("filter_show", source, |source| source.show());
+}
+
+pub(super) unsafe extern "C" fn filter_hide<S>(source: *mut c_void)
+where
+    S: FilterSource,
+{
+    with_source::<S, _>("filter_hide", source, |source| source.hide());
+}
+
+pub(super) unsafe extern "C" fn filter_save<S>(
+    source: *mut c_void,
+    settings: *mut libobs::obs_data,
+) where
+    S: FilterSource,
+{
+    save_source::<S, _>("filter_save", source, settings, |source, settings| {
+        source.save(settings)
+    });
+}
+
+pub(super) unsafe extern "C" fn filter_load<S>(
+    source: *mut c_void,
+    settings: *mut libobs::obs_data,
+) where
+    S: FilterSource,
+{
+    update_source::<S, _>("filter_load", source, settings, |source, settings| {
+        source.load(settings)
+    });
+}
+
+pub(super) unsafe extern "C" fn filter_video_tick<S>(source: *mut c_void, seconds: f32)
+where
+    S: FilterSource,
+{
+    catch_panic("filter_video_tick", || {
+        let source = &mut *(source as *mut SourceData<S>);
+        source.inner.video_tick(seconds);
+    });
+}
+
+pub(super) unsafe extern "C" fn filter_video_render<S>(
+    source: *mut c_void,
+    _effect: *mut libobs::gs_effect,
+) where
+    S: FilterSource,
+{
+    catch_panic("filter_video_render", || {
+        let source = &mut *(source as *mut SourceData<S>);
+        source
+            .inner
+            .filter_video(&ObsFilter(source.source), &RenderToken::new());
+    });
+}
+
+pub(super) unsafe extern "C" fn filter_audio<S>(
+    source: *mut c_void,
+    audio: *mut libobs::obs_audio_data,
+) -> *mut libobs::obs_audio_data
+where
+    S: FilterSource,
+{
+    catch_panic("filter_audio", || {
+        let source = &mut *(source as *mut SourceData<S>);
+        let keep = source
+            .inner
+            .filter_audio(&ObsFilter(source.source), &mut FilterAudio::from_raw(audio));
+        if keep {
+            audio
+        } else {
+            ptr::null_mut()
+        }
+    }).unwrap_or(audio)
+}
+
+pub(super) unsafe extern "C" fn source_activate<S>(source: *mut c_void)
+where
+    S: VideoSource,
+{
+    with_source::<S, _>("source_activate", source, |source| source.activate());
+}
+
+pub(super) unsafe extern "C" fn source_deactivate<S>(source: *mut c_void)
+where
+    S: VideoSource,
+{
+    with_source::<S, _>("source_deactivate", source, |source| source.deactivate());
+}
+
+pub(super) unsafe extern "C" fn source_show<S>(source: *mut c_void)
+where
+    S: VideoSource,
+{
+    with_source::<S, _>("source_show", source, |source| source.show());
+}
+
+pub(super) unsafe extern "C" fn source_hide<S>(source: *mut c_void)
+where
+    S: VideoSource,
+{
+    with_source::<S, _>("source_hide", source, |source| source.hide());
+}
+
+pub(super) unsafe extern "C" fn source_save<S>(
+    source: *mut c_void,
+    settings: *mut libobs::obs_data,
+) where
+    S: VideoSource,
+{
+    save_source::<S, _>("source_save", source, settings, |source, settings| {
+        source.save(settings)
+    });
+}
+
+pub(super) unsafe extern "C" fn source_load<S>(
+    source: *mut c_void,
+    settings: *mut libobs::obs_data,
+) where
+    S: VideoSource,
+{
+    update_source::<S, _>("source_load", source, settings, |source, settings| {
+        source.load(settings)
+    });
+}
+
+pub(super) unsafe extern "C" fn source_enum_active_sources<S>(
+    source: *mut c_void,
+    enum_callback: libobs::obs_source_enum_proc_t,
+    param: *mut c_void,
+) where
+    S: VideoSource,
+{
+    catch_panic("source_enum_active_sources", || {
+        let enum_callback = match enum_callback {
+            Some(enum_callback) => enum_callback,
+            None => return,
+        };
+        let source = &mut *(source as *mut SourceData<S>);
+        let parent = source.source;
+        source
+            .inner
+            .enum_active_sources(&mut |child| enum_callback(parent, child.as_raw(), param));
+    });
+}
+
+pub(super) unsafe extern "C" fn source_mouse_click<S>(
+    source: *mut c_void,
+    event: *const libobs::obs_mouse_event,
+    type_: i32,
+    mouse_up: bool,
+    click_count: u32,
+) where
+    S: VideoSource,
+{
+    catch_panic("source_mouse_click", || {
+        let button = match MouseButton::from_raw(type_) {
+            Some(button) => button,
+            None => return,
+        };
+        let source = &mut (*(source as *mut SourceData<S>)).inner;
+        source.mouse_click(&MouseEvent::from_raw(&*event), button, mouse_up, click_count);
+    });
+}
+
+pub(super) unsafe extern "C" fn source_mouse_move<S>(
+    source: *mut c_void,
+    event: *const libobs::obs_mouse_event,
+    mouse_leave: bool,
+) where
+    S: VideoSource,
+{
+    catch_panic("source_mouse_move", || {
+        let source = &mut (*(source as *mut SourceData<S>)).inner;
+        source.mouse_move(&MouseEvent::from_raw(&*event), mouse_leave);
+    });
+}
+
+pub(super) unsafe extern "C" fn source_mouse_wheel<S>(
+    source: *mut c_void,
+    event: *const libobs::obs_mouse_event,
+    x_delta: i32,
+    y_delta: i32,
+) where
+    S: VideoSource,
+{
+    catch_panic("source_mouse_wheel", || {
+        let source = &mut (*(source as *mut SourceData<S>)).inner;
+        source.mouse_wheel(&MouseEvent::from_raw(&*event), x_delta, y_delta);
+    });
+}
+
+pub(super) unsafe extern "C" fn source_focus<S>(source: *mut c_void, focus: bool)
+where
+    S: VideoSource,
+{
+    catch_panic("source_focus", || {
+        let source = &mut (*(source as *mut SourceData<S>)).inner;
+        source.focus(focus);
+    });
+}
+
+pub(super) unsafe extern "C" fn source_key_click<S>(
+    source: *mut c_void,
+    event: *const libobs::obs_key_event,
+    key_up: bool,
+) where
+    S: VideoSource,
+{
+    catch_panic("source_key_click", || {
+        let source = &mut (*(source as *mut SourceData<S>)).inner;
+        source.key_click(&KeyEvent::from_raw(&*event), key_up);
+    });
+}
@@ -0,0 +1,535 @@
+mod ffi;
+
+use libobs;
+use std::ffi::{CStr, CString};
+use std::{mem, ptr};
+use std::os::raw::{c_char, c_void};
+use super::{AudioFrame, Data, Effect, FilterAudio, KeyEvent, MouseButton, MouseEvent,
+            ProcHandler, Properties, RenderToken, SignalHandler};
+use super::version::{compiled_version, runtime_version};
+
+pub struct ObsSource(*mut libobs::obs_source);
+
+impl ObsSource {
+    // for pointers OBS doesn't give us a reference for
+    pub(super) unsafe fn from_unowned(source: *mut libobs::obs_source) -> Option<Self> {
+        if source.is_null() {
+            None
+        } else {
+            libobs::obs_source_addref(source);
+            Some(ObsSource(source))
+        }
+    }
+
+    // for pointers OBS already gave us a reference for
+    pub(super) unsafe fn from_raw(source: *mut libobs::obs_source) -> Option<Self> {
+        if source.is_null() {
+            None
+        } else {
+            Some(ObsSource(source))
+        }
+    }
+
+    // for the data OBS passes to property callbacks, which is null when the
+    // properties are for a source type rather than a source
+    pub(super) unsafe fn from_source_data(data: *mut c_void) -> Option<Self> {
+        if data.is_null() {
+            None
+        } else {
+            ObsSource::from_unowned(ffi::source_of_data(data))
+        }
+    }
+
+    pub(super) unsafe fn as_raw(&self) -> *mut libobs::obs_source {
+        self.0
+    }
+
+    pub fn get_name(&self) -> String {
+        unsafe {
+            CStr::from_ptr(libobs::obs_source_get_name(self.0))
+                .to_string_lossy()
+                .into_owned()
+        }
+    }
+
+    pub fn update(&self, data: &Data) {
+        unsafe {
+            libobs::obs_source_update(self.0, data.as_raw());
+        }
+    }
+
+    pub fn get_width(&self) -> u32 {
+        unsafe { libobs::obs_source_get_width(self.0) }
+    }
+
+    pub fn get_height(&self) -> u32 {
+        unsafe { libobs::obs_source_get_height(self.0) }
+    }
+
+    pub fn video_render(&self, _token: &RenderToken) {
+        unsafe {
+            libobs::obs_source_video_render(self.0);
+        }
+    }
+
+    pub fn get_properties(&self) -> Properties {
+        unsafe { Properties::from_raw(libobs::obs_source_properties(self.0)) }
+    }
+
+    pub fn get_weak_source(&self) -> ObsWeakSource {
+        unsafe { ObsWeakSource(libobs::obs_source_get_weak_source(self.0)) }
+    }
+
+    pub fn get_signal_handler(&self) -> SignalHandler {
+        unsafe {
+            SignalHandler::from_raw(
+                libobs::obs_source_get_signal_handler(self.0),
+                Some(self.get_weak_source()),
+            )
+        }
+    }
+
+    pub fn get_proc_handler(&self) -> ProcHandler {
+        unsafe {
+            ProcHandler::from_raw(
+                libobs::obs_source_get_proc_handler(self.0),
+                Some(self.get_weak_source()),
+            )
+        }
+    }
+
+    // can be called from any thread. frames that don't have as much data as
+    // they claim are dropped.
+    pub fn output_audio(&self, frame: &AudioFrame) {
+        match frame.as_raw() {
+            Some(audio) => unsafe {
+                libobs::obs_source_output_audio(self.0, &audio);
+            },
+            None => error!("dropping audio frame with missing data"),
+        }
+    }
+}
+
+impl Drop for ObsSource {
+    fn drop(&mut self) {
+        unsafe {
+            libobs::obs_source_release(self.0);
+        }
+    }
+}
+
+impl Clone for ObsSource {
+    fn clone(&self) -> Self {
+        unsafe {
+            libobs::obs_source_addref(self.0);
+        }
+        ObsSource(self.0)
+    }
+}
+
+// the filter a `FilterSource` is attached to. only handed out by reference
+// because the filter owns us, not the other way round.
+pub struct ObsFilter(*mut libobs::obs_source);
+
+impl ObsFilter {
+    pub fn get_name(&self) -> String {
+        unsafe {
+            CStr::from_ptr(libobs::obs_source_get_name(self.0))
+                .to_string_lossy()
+                .into_owned()
+        }
+    }
+
+    pub fn get_weak_source(&self) -> ObsWeakSource {
+        unsafe { ObsWeakSource(libobs::obs_source_get_weak_source(self.0)) }
+    }
+
+    // the source the filter was added to
+    pub fn get_parent(&self) -> Option<ObsSource> {
+        unsafe { ObsSource::from_unowned(libobs::obs_filter_get_parent(self.0)) }
+    }
+
+    // the next filter in the chain, or the parent if this is the last one
+    pub fn get_target(&self) -> Option<ObsSource> {
+        unsafe { ObsSource::from_unowned(libobs::obs_filter_get_target(self.0)) }
+    }
+
+    // starts drawing the target into the filter's texture. when this returns
+    // true `process_filter_end` has to be called to draw the result.
+    pub fn process_filter_begin(&self, allow_direct: bool, _token: &RenderToken) -> bool {
+        let allow_direct = if allow_direct {
+            libobs::obs_allow_direct_render_OBS_ALLOW_DIRECT_RENDERING
+        } else {
+            libobs::obs_allow_direct_render_OBS_NO_DIRECT_RENDERING
+        };
+        unsafe {
+            libobs::obs_source_process_filter_begin(
+                self.0,
+                libobs::gs_color_format_GS_RGBA,
+                allow_direct,
+            )
+        }
+    }
+
+    // draws the texture from `process_filter_begin` with `effect`, or with
+    // OBS's default effect
+    pub fn process_filter_end(
+        &self,
+        effect: Option<&mut Effect>,
+        width: u32,
+        height: u32,
+        _token: &RenderToken,
+    ) {
+        unsafe {
+            let effect = match effect {
+                Some(effect) => effect.as_raw(),
+                None => libobs::obs_get_base_effect(libobs::obs_base_effect_OBS_EFFECT_DEFAULT),
+            };
+            libobs::obs_source_process_filter_end(self.0, effect, width, height);
+        }
+    }
+
+    // draws the target as if this filter wasn't there
+    pub fn skip_video_filter(&self) {
+        unsafe {
+            libobs::obs_source_skip_video_filter(self.0);
+        }
+    }
+}
+
+pub struct ObsWeakSource(*mut libobs::obs_weak_source_t);
+
+impl ObsWeakSource {
+    pub fn upgrade(&self) -> Option<ObsSource> {
+        unsafe { ObsSource::from_raw(libobs::obs_weak_source_get_source(self.0)) }
+    }
+}
+
+impl Clone for ObsWeakSource {
+    fn clone(&self) -> Self {
+        unsafe {
+            libobs::obs_weak_source_addref(self.0);
+        }
+        ObsWeakSource(self.0)
+    }
+}
+
+impl Drop for ObsWeakSource {
+    fn drop(&mut self) {
+        unsafe {
+            libobs::obs_weak_source_release(self.0);
+        }
+    }
+}
+
+unsafe impl Send for ObsWeakSource {}
+unsafe impl Sync for ObsWeakSource {}
+
+pub trait VideoSourceDefinition {
+    type Source: VideoSource;
+    fn create(&self, settings: &Data, source: &mut ObsSource) -> Self::Source;
+    fn get_defaults(&self, _settings: &mut Data) {}
+    // add OBS_SOURCE_INTERACTION to get the mouse, focus and key callbacks
+    fn get_output_flags(&self) -> u32 {
+        libobs::OBS_SOURCE_VIDEO
+    }
+}
+
+pub trait VideoSource {
+    fn update(&mut self, _settings: &Data) {}
+    fn get_width(&self) -> u32;
+    fn get_height(&self) -> u32;
+    fn video_tick(&mut self, _seconds: f32) {}
+    fn video_render(&mut self, _token: &RenderToken) {}
+    fn get_properties(&self) -> Properties {
+        Properties::new()
+    }
+    // called when the source starts and stops being shown on the program
+    // output
+    fn activate(&mut self) {}
+    fn deactivate(&mut self) {}
+    // called when the source starts and stops being visible anywhere,
+    // including previews and projectors
+    fn show(&mut self) {}
+    fn hide(&mut self) {}
+    // for state that isn't part of the settings the user edits
+    fn save(&mut self, _settings: &mut Data) {}
+    fn load(&mut self, _settings: &Data) {}
+    // sources that render other sources should pass each of them to
+    // `enum_callback` so that OBS knows they're active too
+    fn enum_active_sources(&mut self, _enum_callback: &mut FnMut(&ObsSource)) {}
+    fn mouse_click(
+        &mut self,
+        _event: &MouseEvent,
+        _button: MouseButton,
+        _mouse_up: bool,
+        _click_count: u32,
+    ) {
+    }
+    fn mouse_move(&mut self, _event: &MouseEvent, _mouse_leave: bool) {}
+    fn mouse_wheel(&mut self, _event: &MouseEvent, _x_delta: i32, _y_delta: i32) {}
+    fn focus(&mut self, _focus: bool) {}
+    fn key_click(&mut self, _event: &KeyEvent, _key_up: bool) {}
+}
+
+pub trait AudioSourceDefinition {
+    type Source: AudioSource;
+    // keep the weak source from `source.get_weak_source()` to output audio
+    // later. holding on to `source` itself would keep it alive forever.
+    fn create(&self, settings: &Data, source: &mut ObsSource) -> Self::Source;
+    fn get_defaults(&self, _settings: &mut Data) {}
+}
+
+pub trait AudioSource {
+    fn update(&mut self, _settings: &Data) {}
+    fn get_properties(&self) -> Properties {
+        Properties::new()
+    }
+    // see the methods of the same names on VideoSource
+    fn activate(&mut self) {}
+    fn deactivate(&mut self) {}
+    fn show(&mut self) {}
+    fn hide(&mut self) {}
+    fn save(&mut self, _settings: &mut Data) {}
+    fn load(&mut self, _settings: &Data) {}
+}
+
+pub trait FilterSourceDefinition {
+    type Source: FilterSource;
+    fn create(&self, settings: &Data, filter: &ObsFilter) -> Self::Source;
+    fn get_defaults(&self, _settings: &mut Data) {}
+    // OBS_SOURCE_VIDEO, OBS_SOURCE_AUDIO or both, depending on which of
+    // `filter_video` and `filter_audio` should be called
+    fn get_output_flags(&self) -> u32 {
+        libobs::OBS_SOURCE_VIDEO
+    }
+}
+
+pub trait FilterSource {
+    fn update(&mut self, _settings: &Data) {}
+    fn get_properties(&self) -> Properties {
+        Properties::new()
+    }
+    // called as the source being filtered is activated, shown and so on. see
+    // the methods of the same names on VideoSource.
+    fn activate(&mut self) {}
+    fn deactivate(&mut self) {}
+    fn show(&mut self) {}
+    fn hide(&mut self) {}
+    fn save(&mut self, _settings: &mut Data) {}
+    fn load(&mut self, _settings: &Data) {}
+    fn video_tick(&mut self, _seconds: f32) {}
+    // called instead of video_render. usually this is a
+    // `process_filter_begin`/`process_filter_end` pair.
+    fn filter_video(&mut self, filter: &ObsFilter, _token: &RenderToken) {
+        filter.skip_video_filter();
+    }
+    // returning false drops the audio
+    fn filter_audio(&mut self, _filter: &ObsFilter, _audio: &mut FilterAudio) -> bool {
+        true
+    }
+}
+
+// fills in the parts of obs_source_info shared by every kind of source
+unsafe fn create_source_info<D>(id: &str, name: &str, definition: D) -> libobs::obs_source_info {
+    let type_data = Box::new(ffi::SourceDefinition {
+        // make sure `id` lives as long as our registration.
+        // OBS does *not* copy it.
+        id: CString::new(id).unwrap(),
+        name: CString::new(name).unwrap(),
+        inner: definition,
+    });
+    let mut si: libobs::obs_source_info = mem::zeroed();
+    si.id = type_data.id.as_ptr();
+    let type_data = Box::into_raw(type_data);
+    ffi::add_definition(type_data);
+    si.type_data = type_data as *mut c_void;
+    si.free_type_data = Some(ffi::source_free_type_data::<D>);
+    si.type_ = libobs::obs_source_type_OBS_SOURCE_TYPE_INPUT;
+    si.get_name = Some(ffi::source_get_name::<D>);
+    si
+}
+
+// OBS refuses an obs_source_info bigger than its own, so when it's older than
+// the headers only the fields up to the last one the wrapper fills in are
+// passed. all of those exist in MINIMUM_VERSION.
+unsafe fn register_source_info(si: &libobs::obs_source_info) {
+    let size = if runtime_version() >= compiled_version() {
+        mem::size_of::<libobs::obs_source_info>()
+    } else {
+        let start = si as *const libobs::obs_source_info as usize;
+        let end = &si.get_defaults2 as *const _ as usize + mem::size_of_val(&si.get_defaults2);
+        end - start
+    };
+    libobs::obs_register_source_s(si, size);
+}
+
+pub fn register_source<D: 'static>(id: &str, name: &str, definition: D)
+where
+    D: VideoSourceDefinition,
+{
+    unsafe {
+        let output_flags = definition.get_output_flags();
+        let mut si = create_source_info(id, name, definition);
+        si.output_flags = output_flags;
+        si.create = Some(ffi::source_create::<D>);
+        si.destroy = Some(ffi::source_destroy::<D::Source>);
+        si.get_defaults2 = Some(ffi::source_get_defaults::<D>);
+        si.get_properties = Some(ffi::source_get_properties::<D::Source>);
+        si.update = Some(ffi::source_update::<D::Source>);
+        si.get_width = Some(ffi::source_get_width::<D::Source>);
+        si.get_height = Some(ffi::source_get_height::<D::Source>);
+        si.video_tick = Some(ffi::source_video_tick::<D::Source>);
+        si.video_render = Some(ffi::source_video_render::<D::Source>);
+        si.activate = Some(ffi::source_activate::<D::Source>);
+        si.deactivate = Some(ffi::source_deactivate::<D::Source>);
+        si.show = Some(ffi::source_show::<D::Source>);
+        si.hide = Some(ffi::source_hide::<D::Source>);
+        si.save = Some(ffi::source_save::<D::Source>);
+        si.load = Some(ffi::source_load::<D::Source>);
+        si.enum_active_sources = Some(ffi::source_enum_active_sources::<D::Source>);
+        if output_flags & libobs::OBS_SOURCE_INTERACTION != 0 {
+            si.mouse_click = Some(ffi::source_mouse_click::<D::Source>);
+            si.mouse_move = Some(ffi::source_mouse_move::<D::Source>);
+            si.mouse_wheel = Some(ffi::source_mouse_wheel::<D::Source>);
+            si.focus = Some(ffi::source_focus::<D::Source>);
+            si.key_click = Some(ffi::source_key_click::<D::Source>);
+        }
+        register_source_info(&si);
+    }
+}
+
+pub fn register_audio_source<D: 'static>(id: &str, name: &str, definition: D)
+where
+    D: AudioSourceDefinition,
+{
+    unsafe {
+        let mut si = create_source_info(id, name, definition);
+        si.output_flags = libobs::OBS_SOURCE_AUDIO;
+        si.create = Some(ffi::audio_source_create::<D>);
+        si.destroy = Some(ffi::source_destroy::<D::Source>);
+        si.get_defaults2 = Some(ffi::audio_source_get_defaults::<D>);
+        si.get_properties = Some(ffi::audio_source_get_properties::<D::Source>);
+        si.update = Some(ffi::audio_source_update::<D::Source>);
+        si.activate = Some(ffi::audio_source_activate::<D::Source>);
+        si.deactivate = Some(ffi::audio_source_deactivate::<D::Source>);
+        si.show = Some(ffi::audio_source_show::<D::Source>);
+        si.hide = Some(ffi::audio_source_hide::<D::Source>);
+        si.save = Some(ffi::audio_source_save::<D::Source>);
+        si.load = Some(ffi::audio_source_load::<D::Source>);
+        register_source_info(&si);
+    }
+}
+
+pub fn register_filter<D: 'static>(id: &str, name: &str, definition: D)
+where
+    D: FilterSourceDefinition,
+{
+    unsafe {
+        let output_flags = definition.get_output_flags();
+        let mut si = create_source_info(id, name, definition);
+        si.type_ = libobs::obs_source_type_OBS_SOURCE_TYPE_FILTER;
+        si.output_flags = output_flags;
+        si.create = Some(ffi::filter_create::<D>);
+        si.destroy = Some(ffi::source_destroy::<D::Source>);
+        si.get_defaults2 = Some(ffi::filter_get_defaults::<D>);
+        si.get_properties = Some(ffi::filter_get_properties::<D::Source>);
+        si.update = Some(ffi::filter_update::<D::Source>);
+        si.activate = Some(ffi::filter_activate::<D::Source>);
+        si.deactivate = Some(ffi::filter_deactivate::<D::Source>);
+        si.show = Some(ffi::filter_show::<D::Source>);
+        si.hide = Some(ffi::filter_hide::<D::Source>);
+        si.save = Some(ffi::filter_save::<D::Source>);
+        si.load = Some(ffi::filter_load::<D::Source>);
+        if output_flags & libobs::OBS_SOURCE_VIDEO != 0 {
+            si.video_tick = Some(ffi::filter_video_tick::<D::Source>);
+            si.video_render = Some(ffi::filter_video_render::<D::Source>);
+        }
+        if output_flags & libobs::OBS_SOURCE_AUDIO != 0 {
+            si.filter_audio = Some(ffi::filter_audio::<D::Source>);
+        }
+        register_source_info(&si);
+    }
+}
+
+pub fn source_create_private(
+    id: &str,
+    name: Option<&str>,
+    settings: Option<&Data>,
+) -> Option<ObsSource> {
+    let id = CString::new(id).unwrap();
+    let name = name.map(|n| CString::new(n).unwrap());
+    unsafe {
+        ObsSource::from_raw(libobs::obs_source_create_private(
+            id.as_ptr(),
+            name.as_ref().map(|n| n.as_ptr()).unwrap_or(ptr::null()),
+            settings.map(|s| s.as_raw()).unwrap_or(ptr::null_mut()),
+        ))
+    }
+}
+
+pub fn get_source_defaults(id: &str) -> Option<Data> {
+    unsafe {
+        let id = CString::new(id).unwrap();
+        let data = libobs::obs_get_source_defaults(id.as_ptr());
+        if data.is_null() {
+            None
+        } else {
+            Some(Data::from_raw(data))
+        }
+    }
+}
+
+pub struct SourceType {
+    id: String,
+}
+
+impl SourceType {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn get_display_name(&self) -> Option<String> {
+        unsafe {
+            let id = CString::new(self.id.as_str()).unwrap();
+            let name = libobs::obs_source_get_display_name(id.as_ptr());
+            if name.is_null() {
+                None
+            } else {
+                Some(CStr::from_ptr(name).to_string_lossy().into_owned())
+            }
+        }
+    }
+
+    pub fn get_output_flags(&self) -> u32 {
+        unsafe {
+            let id = CString::new(self.id.as_str()).unwrap();
+            libobs::obs_get_source_output_flags(id.as_ptr())
+        }
+    }
+
+    pub fn get_properties(&self) -> Option<Properties> {
+        unsafe {
+            let id = CString::new(self.id.as_str()).unwrap();
+            let properties = libobs::obs_get_source_properties(id.as_ptr());
+            if properties.is_null() {
+                None
+            } else {
+                Some(Properties::from_raw(properties))
+            }
+        }
+    }
+}
+
+pub fn enum_input_types() -> Vec<SourceType> {
+    let mut types = Vec::new();
+    unsafe {
+        let mut id: *const c_char = ptr::null();
+        while libobs::obs_enum_input_types(types.len(), &mut id) {
+            types.push(SourceType {
+                id: CStr::from_ptr(id).to_string_lossy().into_owned(),
+            });
+        }
+    }
+    types
+}
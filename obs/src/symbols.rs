@@ -0,0 +1,101 @@
+// functions from versions of libobs newer than the oldest one we support.
+// linking to them would stop the plugin from loading at all in older versions,
+// before `check_version` gets a chance to run, so they're looked up by name
+// once OBS is running instead.
+
+use std::mem;
+use std::os::raw::{c_char, c_int};
+use libobs;
+use super::runtime_version;
+
+// obs_group_type, which older bindings don't have
+pub(super) const OBS_GROUP_NORMAL: c_int = 1;
+pub(super) const OBS_GROUP_CHECKABLE: c_int = 2;
+
+pub(super) type AddGroup = unsafe extern "C" fn(
+    props: *mut libobs::obs_properties_t,
+    name: *const c_char,
+    description: *const c_char,
+    group_type: c_int,
+    group: *mut libobs::obs_properties_t,
+) -> *mut libobs::obs_property_t;
+
+pub(super) type GetParent =
+    unsafe extern "C" fn(props: *mut libobs::obs_properties_t) -> *mut libobs::obs_properties_t;
+
+lazy_static! {
+    static ref ADD_GROUP: Option<usize> = find_symbol("obs_properties_add_group");
+    static ref GET_PARENT: Option<usize> = find_symbol("obs_properties_get_parent");
+}
+
+#[cfg(all(unix, not(feature = "fake")))]
+fn find_symbol(name: &str) -> Option<usize> {
+    use std::ffi::CString;
+    use std::os::raw::c_void;
+    // RTLD_DEFAULT, which searches everything that's loaded, including libobs
+    #[cfg(target_os = "macos")]
+    const DEFAULT: isize = -2;
+    #[cfg(not(target_os = "macos"))]
+    const DEFAULT: isize = 0;
+    #[cfg_attr(target_os = "linux", link(name = "dl"))]
+    extern "C" {
+        fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+    }
+    let name = CString::new(name).unwrap();
+    let symbol = unsafe { dlsym(DEFAULT as *mut c_void, name.as_ptr()) };
+    if symbol.is_null() {
+        None
+    } else {
+        Some(symbol as usize)
+    }
+}
+
+#[cfg(all(windows, not(feature = "fake")))]
+fn find_symbol(name: &str) -> Option<usize> {
+    use std::ffi::CString;
+    use std::os::raw::c_void;
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetModuleHandleA(name: *const c_char) -> *mut c_void;
+        fn GetProcAddress(module: *mut c_void, name: *const c_char) -> *mut c_void;
+    }
+    let name = CString::new(name).unwrap();
+    unsafe {
+        let module = GetModuleHandleA(b"obs.dll\0".as_ptr() as *const c_char);
+        if module.is_null() {
+            return None;
+        }
+        let symbol = GetProcAddress(module, name.as_ptr());
+        if symbol.is_null() {
+            None
+        } else {
+            Some(symbol as usize)
+        }
+    }
+}
+
+// the fake is linked in statically, where the dynamic linker can't see it
+#[cfg(feature = "fake")]
+fn find_symbol(name: &str) -> Option<usize> {
+    match name {
+        "obs_properties_add_group" => Some(libobs::obs_properties_add_group as usize),
+        "obs_properties_get_parent" => Some(libobs::obs_properties_get_parent as usize),
+        _ => None,
+    }
+}
+
+// None if OBS is older than 22, which introduced groups
+pub(super) fn add_group() -> Option<AddGroup> {
+    if !runtime_version().at_least(22, 0) {
+        return None;
+    }
+    ADD_GROUP.map(|f| unsafe { mem::transmute::<usize, AddGroup>(f) })
+}
+
+// None if OBS is older than 22, where properties have no parents
+pub(super) fn get_parent() -> Option<GetParent> {
+    if !runtime_version().at_least(22, 0) {
+        return None;
+    }
+    GET_PARENT.map(|f| unsafe { mem::transmute::<usize, GetParent>(f) })
+}
@@ -0,0 +1,252 @@
+use image::RgbaImage;
+use std::marker::PhantomData;
+use std::ptr;
+use std::slice;
+use libobs;
+use super::{enter_graphics, RenderToken};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorFormat {
+    Rgba,
+    Bgra,
+    // a single channel, which shaders see as red
+    R8,
+}
+
+impl ColorFormat {
+    pub fn bytes_per_pixel(self) -> usize {
+        match self {
+            ColorFormat::Rgba | ColorFormat::Bgra => 4,
+            ColorFormat::R8 => 1,
+        }
+    }
+
+    fn as_raw(self) -> libobs::gs_color_format {
+        match self {
+            ColorFormat::Rgba => libobs::gs_color_format_GS_RGBA,
+            ColorFormat::Bgra => libobs::gs_color_format_GS_BGRA,
+            ColorFormat::R8 => libobs::gs_color_format_GS_R8,
+        }
+    }
+}
+
+pub struct Texture {
+    texture: *mut libobs::gs_texture_t,
+    width: u32,
+    height: u32,
+    format: ColorFormat,
+    dynamic: bool,
+}
+
+impl Texture {
+    // `data` is the rows of the image with no padding between them. `data`
+    // may only be null for dynamic textures. OBS reads one image per level,
+    // and there's only `data`, so `levels` has to be 1.
+    unsafe fn create(
+        width: u32,
+        height: u32,
+        format: ColorFormat,
+        data: *const u8,
+        levels: u32,
+        flags: u32,
+    ) -> Result<Self, String> {
+        let mut planes = [data];
+        let texture = libobs::gs_texture_create(
+            width,
+            height,
+            format.as_raw(),
+            levels,
+            if data.is_null() {
+                ptr::null_mut()
+            } else {
+                planes.as_mut_ptr()
+            },
+            flags,
+        );
+        if texture.is_null() {
+            return Err(format!(
+                "could not create a {}x{} {:?} texture",
+                width, height, format
+            ));
+        }
+        Ok(Texture {
+            texture,
+            width,
+            height,
+            format,
+            dynamic: flags & libobs::GS_DYNAMIC != 0,
+        })
+    }
+
+    fn check_size(
+        width: u32,
+        height: u32,
+        format: ColorFormat,
+        data: &[u8],
+    ) -> Result<(), String> {
+        let expected = width as usize * height as usize * format.bytes_per_pixel();
+        if data.len() == expected {
+            Ok(())
+        } else {
+            Err(format!(
+                "expected {} bytes for a {}x{} {:?} image but got {}",
+                expected,
+                width,
+                height,
+                format,
+                data.len()
+            ))
+        }
+    }
+
+    pub fn new(image: &RgbaImage, _token: &RenderToken) -> Result<Self, String> {
+        let (width, height) = image.dimensions();
+        unsafe { Texture::create(width, height, ColorFormat::Rgba, image.as_ptr(), 1, 0) }
+    }
+
+    // OBS only builds mipmaps for textures with power of two sizes and
+    // quietly leaves them out otherwise. only the full size image is passed,
+    // and the graphics driver makes the smaller levels from it.
+    pub fn with_mipmaps(image: &RgbaImage, _token: &RenderToken) -> Result<Self, String> {
+        let (width, height) = image.dimensions();
+        unsafe {
+            Texture::create(
+                width,
+                height,
+                ColorFormat::Rgba,
+                image.as_ptr(),
+                1,
+                libobs::GS_BUILD_MIPMAPS,
+            )
+        }
+    }
+
+    // `data` is the rows of the image with no padding between them.
+    pub fn from_data(
+        width: u32,
+        height: u32,
+        format: ColorFormat,
+        data: &[u8],
+        _token: &RenderToken,
+    ) -> Result<Self, String> {
+        Texture::check_size(width, height, format, data)?;
+        unsafe { Texture::create(width, height, format, data.as_ptr(), 1, 0) }
+    }
+
+    // the contents start out undefined and can be changed with `set_image` or
+    // `map`.
+    pub fn new_dynamic(
+        width: u32,
+        height: u32,
+        format: ColorFormat,
+        _token: &RenderToken,
+    ) -> Result<Self, String> {
+        unsafe { Texture::create(width, height, format, ptr::null(), 1, libobs::GS_DYNAMIC) }
+    }
+
+    pub(super) unsafe fn as_raw(&self) -> *mut libobs::gs_texture_t {
+        self.texture
+    }
+
+    // replaces the contents of a dynamic texture. `data` is the rows of the
+    // image with no padding between them, so it has to be exactly the size of
+    // the texture.
+    pub fn set_image(&mut self, data: &[u8], _token: &RenderToken) -> Result<(), String> {
+        if !self.dynamic {
+            return Err("cannot change the image of a static texture".to_string());
+        }
+        Texture::check_size(self.width, self.height, self.format, data)?;
+        unsafe {
+            libobs::gs_texture_set_image(
+                self.texture,
+                data.as_ptr(),
+                self.width * self.format.bytes_per_pixel() as u32,
+                false,
+            );
+        }
+        Ok(())
+    }
+
+    // gives direct access to the memory of a dynamic texture. it's unmapped
+    // when the `TextureMap` is dropped.
+    pub fn map<'a>(&'a mut self, _token: &'a RenderToken) -> Option<TextureMap<'a>> {
+        if !self.dynamic {
+            return None;
+        }
+        let mut data: *mut u8 = ptr::null_mut();
+        let mut linesize = 0u32;
+        unsafe {
+            if !libobs::gs_texture_map(self.texture, &mut data, &mut linesize) {
+                return None;
+            }
+        }
+        Some(TextureMap {
+            texture: self,
+            data,
+            linesize,
+            _token: PhantomData,
+        })
+    }
+
+    pub fn draw(&self, _token: &RenderToken) {
+        unsafe {
+            libobs::obs_source_draw(self.texture, 0, 0, 0, 0, false);
+        }
+    }
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+    pub fn format(&self) -> ColorFormat {
+        self.format
+    }
+    pub fn is_dynamic(&self) -> bool {
+        self.dynamic
+    }
+}
+impl Drop for Texture {
+    fn drop(&mut self) {
+        let _graphics = enter_graphics();
+        unsafe {
+            libobs::gs_texture_destroy(self.texture);
+        }
+    }
+}
+unsafe impl Send for Texture {}
+unsafe impl Sync for Texture {}
+
+pub struct TextureMap<'a> {
+    texture: &'a mut Texture,
+    data: *mut u8,
+    linesize: u32,
+    _token: PhantomData<&'a RenderToken>,
+}
+
+impl<'a> TextureMap<'a> {
+    // bytes from the start of one row to the next, which may be more than the
+    // width of the texture
+    pub fn linesize(&self) -> usize {
+        self.linesize as usize
+    }
+
+    pub fn row_mut(&mut self, y: u32) -> &mut [u8] {
+        assert!(y < self.texture.height, "row {} is outside of the texture", y);
+        let width = self.texture.width as usize * self.texture.format.bytes_per_pixel();
+        unsafe {
+            slice::from_raw_parts_mut(
+                self.data.offset((y as usize * self.linesize()) as isize),
+                width,
+            )
+        }
+    }
+}
+
+impl<'a> Drop for TextureMap<'a> {
+    fn drop(&mut self) {
+        unsafe {
+            libobs::gs_texture_unmap(self.texture.texture);
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use std::fmt;
+use libobs;
+
+// the oldest OBS the wrapper works with. it has to agree with
+// MINIMUM_OBS_VERSION in libobs-sys's build script.
+pub const MINIMUM_VERSION: ObsVersion = ObsVersion {
+    major: 21,
+    minor: 0,
+    patch: 0,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ObsVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl ObsVersion {
+    pub fn new(major: u32, minor: u32, patch: u32) -> Self {
+        ObsVersion {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    // the format of obs_get_version and obs_module_ver
+    pub fn from_packed(version: u32) -> Self {
+        ObsVersion {
+            major: version >> 24,
+            minor: (version >> 16) & 0xff,
+            patch: version & 0xffff,
+        }
+    }
+
+    pub fn packed(self) -> u32 {
+        (self.major << 24) | (self.minor << 16) | self.patch
+    }
+
+    pub fn at_least(self, major: u32, minor: u32) -> bool {
+        (self.major, self.minor) >= (major, minor)
+    }
+}
+
+impl fmt::Display for ObsVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+// the version of the headers the plugin was built against
+pub fn compiled_version() -> ObsVersion {
+    ObsVersion::new(
+        libobs::LIBOBS_API_MAJOR_VER as u32,
+        libobs::LIBOBS_API_MINOR_VER as u32,
+        libobs::LIBOBS_API_PATCH_VER as u32,
+    )
+}
+
+// the version of the OBS that loaded the plugin, which may be older or newer
+// than the headers
+pub fn runtime_version() -> ObsVersion {
+    unsafe { ObsVersion::from_packed(libobs::obs_get_version()) }
+}
+
+// logs why and returns false if the plugin can't run in this OBS
+pub fn check_version() -> bool {
+    let runtime = runtime_version();
+    let compiled = compiled_version();
+    if runtime < MINIMUM_VERSION {
+        error!(
+            "OBS {} is too old. this plugin needs at least OBS {}.",
+            runtime, MINIMUM_VERSION
+        );
+        return false;
+    }
+    if runtime.major > compiled.major {
+        // OBS only appends to its structs, so this should still work
+        info!(
+            "running in OBS {}, which is newer than the OBS {} this plugin was built for",
+            runtime, compiled
+        );
+    } else if runtime < compiled {
+        info!(
+            "running in OBS {}, which is older than the OBS {} this plugin was built for. \
+             features OBS doesn't have yet are turned off.",
+            runtime, compiled
+        );
+    }
+    true
+}
@@ -0,0 +1,656 @@
+// runs the wrapper against the fake libobs: `cargo test -p obs --features fake`
+#![cfg(feature = "fake")]
+
+#[macro_use]
+extern crate lazy_static;
+#[macro_use]
+extern crate obs;
+#[macro_use]
+extern crate obs_derive;
+
+use obs::fake;
+use obs::{AudioFormat, AudioFrame, AudioSource, AudioSourceDefinition, BlendType, CallData,
+          ColorFormat, Data, DataArray, GroupType, MainRenderCallback, ObsSource, Properties,
+          RenderToken, Settings, SignalConnection, SpeakerLayout, Texture, TextureRender,
+          VideoSource, VideoSourceDefinition};
+use std::cell::Cell;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+lazy_static! {
+    static ref GLOBALS: Mutex<()> = Mutex::new(());
+}
+
+// the fake's version and log are shared by every test, and the version decides
+// how sources are registered and whether groups work. tests that use either
+// hold this so they don't run at the same time, and the version goes back to
+// normal afterwards even if the test fails.
+struct Globals(MutexGuard<'static, ()>);
+
+impl Drop for Globals {
+    fn drop(&mut self) {
+        let compiled = obs::compiled_version();
+        fake::set_version(compiled.major, compiled.minor, compiled.patch);
+    }
+}
+
+fn lock_globals() -> Globals {
+    // a failed test poisons the lock, which shouldn't fail the rest
+    Globals(GLOBALS.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+// makes sure the macros only need `obs` in scope
+obs_declare_module!(TestModule, "obs-test", "Tests the obs crate.");
+
+struct TestModule;
+
+impl obs::Module<TestModule> for TestModule {
+    fn load() -> Option<Self> {
+        Some(TestModule)
+    }
+}
+
+#[derive(Settings)]
+struct SizeSettings {
+    #[obs(label = "Width", default = 4)]
+    width: u32,
+    #[obs(label = "Height", default = 2)]
+    height: u32,
+}
+
+struct SizeSourceDefinition;
+
+impl VideoSourceDefinition for SizeSourceDefinition {
+    type Source = SizeSource;
+    fn create(&self, settings: &Data, _source: &mut ObsSource) -> SizeSource {
+        SizeSource(SizeSettings::from_data(settings))
+    }
+    fn get_defaults(&self, settings: &mut Data) {
+        SizeSettings::set_defaults(settings);
+    }
+}
+
+struct SizeSource(SizeSettings);
+
+impl VideoSource for SizeSource {
+    fn update(&mut self, settings: &Data) {
+        self.0 = SizeSettings::from_data(settings);
+    }
+    fn get_width(&self) -> u32 {
+        self.0.width
+    }
+    fn get_height(&self) -> u32 {
+        self.0.height
+    }
+}
+
+fn sample_data() -> Data {
+    let mut inner = Data::new();
+    inner.set_string("name", "inner");
+
+    let mut items = DataArray::new();
+    for i in 0..3 {
+        let mut item = Data::new();
+        item.set_int("index", i);
+        items.push(&item);
+    }
+
+    let mut data = Data::new();
+    data.set_string("text", "hello");
+    data.set_int("count", 42);
+    data.set_double("ratio", 1.5);
+    data.set_bool("enabled", true);
+    data.set_obj("inner", &inner);
+    data.set_array("items", &items);
+    data
+}
+
+fn assert_sample_data(data: &Data) {
+    assert_eq!(data.get_string("text"), Some("hello".to_string()));
+    assert_eq!(data.get_int("count"), 42);
+    assert_eq!(data.get_double("ratio"), 1.5);
+    assert!(data.get_bool("enabled"));
+    let inner = data.get_obj("inner").unwrap();
+    assert_eq!(inner.get_string("name"), Some("inner".to_string()));
+    let indices: Vec<i64> = data
+        .get_array("items")
+        .unwrap()
+        .iter()
+        .map(|item| item.get_int("index"))
+        .collect();
+    assert_eq!(indices, vec![0, 1, 2]);
+}
+
+// a path in the temporary directory that no other test uses
+fn temp_path(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("obs-test-{}-{}", process::id(), name))
+}
+
+#[test]
+fn data_round_trips_through_json() {
+    let json = sample_data().get_json();
+    assert_sample_data(&Data::from_json(&json).unwrap());
+}
+
+#[test]
+fn data_round_trips_through_files() {
+    let path = temp_path("data.json");
+    let backup = temp_path("data.json.bak");
+    let data = sample_data();
+
+    assert!(data.save_json(&path));
+    assert_sample_data(&Data::from_json_file(&path).unwrap());
+
+    // the second save moves the first one to the backup
+    assert!(data.save_json_safe(&path, "tmp", "bak"));
+    assert!(data.save_json_safe(&path, "tmp", "bak"));
+    fs::remove_file(&path).unwrap();
+    assert!(Data::from_json_file(&path).is_none());
+    assert_sample_data(&Data::from_json_file_safe(&path, "bak").unwrap());
+
+    fs::remove_file(&backup).unwrap();
+    assert!(Data::from_json_file_safe(&path, "bak").is_none());
+}
+
+#[test]
+fn data_rejects_paths_obs_cannot_open() {
+    let data = sample_data();
+    assert!(!data.save_json("bad\0path.json"));
+    assert!(!data.save_json_safe("bad\0path.json", "tmp", "bak"));
+    assert!(Data::from_json_file("bad\0path.json").is_none());
+}
+
+#[test]
+fn data_arrays_keep_their_order() {
+    let item = |i| {
+        let mut item = Data::new();
+        item.set_int("index", i);
+        item
+    };
+    let indices = |array: &DataArray| -> Vec<i64> {
+        array.iter().map(|item| item.get_int("index")).collect()
+    };
+
+    let mut array = DataArray::new();
+    assert!(array.is_empty());
+    assert_eq!(array.push(&item(0)), 0);
+    assert_eq!(array.push(&item(2)), 1);
+    array.insert(1, &item(1));
+    assert_eq!(indices(&array), vec![0, 1, 2]);
+    array.remove(0);
+    assert_eq!(indices(&array), vec![1, 2]);
+    assert_eq!(array.len(), 2);
+    assert!(array.get(2).is_none());
+}
+
+#[test]
+fn data_defaults_are_not_user_values() {
+    let mut data = Data::new();
+    data.set_default_int("count", 3);
+    data.set_default_string("text", "default");
+    assert_eq!(data.get_int("count"), 3);
+    assert!(data.has_default_value("count"));
+    assert!(!data.has_user_value("count"));
+
+    data.set_int("count", 5);
+    assert_eq!(data.get_int("count"), 5);
+    assert_eq!(data.get_default_int("count"), 3);
+    // only user values are saved
+    let saved = Data::from_json(&data.get_json()).unwrap();
+    assert_eq!(saved.get_int("count"), 5);
+    assert!(!saved.has_user_value("text"));
+
+    data.unset_user_value("count");
+    assert_eq!(data.get_int("count"), 3);
+
+    // every value in the other data becomes a default here
+    let mut copy = Data::new();
+    copy.apply_defaults(&sample_data());
+    assert_eq!(copy.get_int("count"), 42);
+    assert!(!copy.has_user_value("count"));
+    let inner = copy.get_obj("inner").unwrap();
+    assert_eq!(inner.get_string("name"), Some("inner".to_string()));
+    // OBS has no default arrays
+    assert!(copy.get_array("items").is_none());
+}
+
+#[test]
+fn data_deep_copies_share_nothing() {
+    let mut data = sample_data();
+    data.set_default_string("fallback", "default");
+    let copy = data.deep_copy();
+    assert_eq!(copy.get_string("fallback"), Some("default".to_string()));
+
+    data.set_int("count", 1);
+    data.get_obj("inner").unwrap().set_string("name", "changed");
+    assert_eq!(copy.get_int("count"), 42);
+    let inner = copy.get_obj("inner").unwrap();
+    assert_eq!(inner.get_string("name"), Some("inner".to_string()));
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, SettingsValue)]
+enum Shape {
+    #[obs(value = "sq", label = "Square")]
+    Square,
+    Circle,
+}
+
+impl Default for Shape {
+    fn default() -> Self {
+        Shape::Circle
+    }
+}
+
+#[derive(Debug, PartialEq, Settings)]
+struct DerivedSettings {
+    #[obs(key = "display_name", label = "Name", default = "untitled")]
+    name: String,
+    #[obs(default = true)]
+    visible: bool,
+    #[obs(default = 1.5, min = 0.0, max = 10.0, slider)]
+    scale: f64,
+    #[obs(default)]
+    shape: Shape,
+    count: i32,
+}
+
+#[test]
+fn derived_settings_have_defaults() {
+    let mut data = Data::new();
+    DerivedSettings::set_defaults(&mut data);
+    assert_eq!(
+        DerivedSettings::from_data(&data),
+        DerivedSettings {
+            name: "untitled".to_string(),
+            visible: true,
+            scale: 1.5,
+            shape: Shape::Circle,
+            count: 0,
+        }
+    );
+    assert!(data.has_default_value("display_name"));
+    assert!(!data.has_user_value("display_name"));
+    assert!(!data.has_default_value("count"));
+}
+
+#[test]
+fn derived_settings_use_their_keys() {
+    let mut data = Data::new();
+    DerivedSettings {
+        name: "card".to_string(),
+        visible: false,
+        scale: 2.0,
+        shape: Shape::Square,
+        count: 3,
+    }.to_data(&mut data);
+    assert_eq!(data.get_string("display_name"), Some("card".to_string()));
+    assert!(!data.has_user_value("name"));
+    assert_eq!(data.get_string("shape"), Some("sq".to_string()));
+    assert_eq!(
+        fake::property_names(&DerivedSettings::properties()),
+        vec!["display_name", "visible", "scale", "shape", "count"]
+    );
+
+    // unknown values read as the first variant
+    data.set_string("shape", "triangle");
+    assert_eq!(DerivedSettings::from_data(&data).shape, Shape::Square);
+}
+
+#[test]
+fn derived_settings_round_trip() {
+    let settings = DerivedSettings {
+        name: "card".to_string(),
+        visible: true,
+        scale: 0.25,
+        shape: Shape::Square,
+        count: -7,
+    };
+    let mut data = Data::new();
+    settings.to_data(&mut data);
+    let saved = Data::from_json(&data.get_json()).unwrap();
+    assert_eq!(DerivedSettings::from_data(&saved), settings);
+}
+
+#[test]
+fn sources_use_their_settings() {
+    let _globals = lock_globals();
+    obs::register_source("obs-test-size", "Size", SizeSourceDefinition);
+    assert!(fake::registered_source_ids().contains(&"obs-test-size".to_string()));
+
+    let source = obs::source_create_private("obs-test-size", None, None).unwrap();
+    assert_eq!((source.get_width(), source.get_height()), (4, 2));
+
+    let mut data = Data::new();
+    SizeSettings {
+        width: 16,
+        height: 9,
+    }.to_data(&mut data);
+    source.update(&data);
+    assert_eq!((source.get_width(), source.get_height()), (16, 9));
+}
+
+struct PanicSourceDefinition;
+
+impl VideoSourceDefinition for PanicSourceDefinition {
+    type Source = PanicSource;
+    fn create(&self, _settings: &Data, _source: &mut ObsSource) -> PanicSource {
+        PanicSource
+    }
+}
+
+struct PanicSource;
+
+impl VideoSource for PanicSource {
+    fn get_width(&self) -> u32 {
+        8
+    }
+    fn get_height(&self) -> u32 {
+        8
+    }
+    fn video_render(&mut self, token: &RenderToken) {
+        let mut render = TextureRender::new(token);
+        render.render(token, 8, 8, 0.0, 0.0, || {
+            obs::with_matrix(token, |_| {
+                obs::with_blend(token, BlendType::One, BlendType::Zero, || {
+                    panic!("render failed");
+                })
+            })
+        });
+    }
+}
+
+#[test]
+fn panicking_sources_undo_their_graphics_state() {
+    let _globals = lock_globals();
+    obs::register_source("obs-test-panic", "Panic", PanicSourceDefinition);
+    let source = obs::source_create_private("obs-test-panic", None, None).unwrap();
+
+    let graphics = obs::enter_graphics();
+    source.video_render(graphics.token());
+    source.video_render(graphics.token());
+
+    assert_eq!(fake::stack_depths(), fake::StackDepths::default());
+    let panics = fake::take_log()
+        .into_iter()
+        .filter(|&(_, ref message)| message == "panic in source_video_render: render failed")
+        .count();
+    assert_eq!(panics, 2);
+}
+
+#[test]
+fn textures_report_failures() {
+    let graphics = obs::enter_graphics();
+    let token = graphics.token();
+
+    assert!(Texture::new_dynamic(0, 4, ColorFormat::Rgba, token).is_err());
+    assert!(Texture::from_data(2, 2, ColorFormat::R8, &[0; 3], token).is_err());
+
+    let mut texture = Texture::from_data(2, 2, ColorFormat::R8, &[0; 4], token).unwrap();
+    assert!(texture.map(token).is_none());
+    texture.draw(token);
+}
+
+#[test]
+fn dynamic_textures_can_be_changed() {
+    let graphics = obs::enter_graphics();
+    let token = graphics.token();
+
+    let mut texture = Texture::new_dynamic(2, 1, ColorFormat::Rgba, token).unwrap();
+    texture.set_image(&[1, 2, 3, 4, 5, 6, 7, 8], token).unwrap();
+    assert_eq!(fake::texture_data(&texture), vec![1, 2, 3, 4, 5, 6, 7, 8]);
+    assert!(texture.set_image(&[0; 4], token).is_err());
+
+    let static_texture = Texture::from_data(1, 1, ColorFormat::Bgra, &[9; 4], token).unwrap();
+    assert_eq!(fake::texture_data(&static_texture), vec![9; 4]);
+}
+
+#[test]
+fn property_callbacks_are_called() {
+    let clicks = Rc::new(Cell::new(0));
+    let modified = Rc::new(Cell::new(0));
+
+    let mut properties = Properties::new();
+    properties.add_bool("enabled", "Enabled");
+    let button_clicks = clicks.clone();
+    properties.add_button("refresh", "Refresh", move |_, source| {
+        assert!(source.is_none());
+        button_clicks.set(button_clicks.get() + 1);
+        true
+    });
+    let modified_value = modified.clone();
+    properties
+        .add_int("count", "Count", 0, 10, 1)
+        .set_modified_callback(move |_, settings| {
+            modified_value.set(settings.get_int("count"));
+            false
+        });
+    assert_eq!(
+        fake::property_names(&properties),
+        vec!["enabled", "refresh", "count"]
+    );
+
+    assert!(fake::click_button(&mut properties, "refresh", None));
+    assert!(!fake::click_button(&mut properties, "enabled", None));
+    assert_eq!(clicks.get(), 1);
+
+    let mut settings = Data::new();
+    settings.set_int("count", 7);
+    assert!(!fake::modify_property(&mut properties, "count", &settings));
+    assert_eq!(modified.get(), 7);
+}
+
+struct ButtonSourceDefinition(Arc<Mutex<Vec<String>>>);
+
+impl VideoSourceDefinition for ButtonSourceDefinition {
+    type Source = ButtonSource;
+    fn create(&self, _settings: &Data, _source: &mut ObsSource) -> ButtonSource {
+        ButtonSource(self.0.clone())
+    }
+}
+
+// has a button inside a group that records which source it was clicked for
+struct ButtonSource(Arc<Mutex<Vec<String>>>);
+
+impl VideoSource for ButtonSource {
+    fn get_width(&self) -> u32 {
+        0
+    }
+    fn get_height(&self) -> u32 {
+        0
+    }
+    fn get_properties(&self) -> Properties {
+        let clicked = self.0.clone();
+        let mut group = Properties::new();
+        group.add_button("reset", "Reset", move |properties, source| {
+            clicked.lock().unwrap().push(source.unwrap().get_name());
+            // callbacks are given the source's properties, not the group
+            properties.get_property("enabled").unwrap().set_enabled(false);
+            true
+        });
+        let mut properties = Properties::new();
+        properties.add_bool("enabled", "Enabled");
+        properties.add_group("advanced", "Advanced", GroupType::Normal, group);
+        properties
+    }
+}
+
+#[test]
+fn grouped_buttons_are_given_their_source() {
+    let _globals = lock_globals();
+    let clicked = Arc::new(Mutex::new(Vec::new()));
+    obs::register_source("obs-test-button", "Button", ButtonSourceDefinition(clicked.clone()));
+    let source = obs::source_create_private("obs-test-button", Some("buttons"), None).unwrap();
+    let mut properties = source.get_properties();
+    assert_eq!(
+        fake::property_names(&properties),
+        vec!["enabled", "advanced", "reset"]
+    );
+    assert!(fake::click_button(&mut properties, "reset", Some(&source)));
+    assert_eq!(*clicked.lock().unwrap(), vec!["buttons"]);
+}
+
+#[test]
+fn main_render_callbacks_run_every_frame() {
+    let sizes = Arc::new(Mutex::new(Vec::new()));
+    let callback_sizes = sizes.clone();
+    let callback = MainRenderCallback::new(move |_, cx, cy| {
+        callback_sizes.lock().unwrap().push((cx, cy));
+    });
+    fake::render_frame(3, 4);
+    fake::render_frame(5, 6);
+    drop(callback);
+    fake::render_frame(7, 8);
+    assert_eq!(*sizes.lock().unwrap(), vec![(3, 4), (5, 6)]);
+}
+
+struct ToneSourceDefinition;
+
+impl AudioSourceDefinition for ToneSourceDefinition {
+    type Source = ToneSource;
+    fn create(&self, _settings: &Data, _source: &mut ObsSource) -> ToneSource {
+        ToneSource
+    }
+}
+
+struct ToneSource;
+
+impl AudioSource for ToneSource {}
+
+#[test]
+fn audio_sources_output_whole_frames() {
+    let _globals = lock_globals();
+    obs::register_audio_source("obs-test-tone", "Tone", ToneSourceDefinition);
+    let source = obs::source_create_private("obs-test-tone", None, None).unwrap();
+
+    let samples = [0u8; 32];
+    let left: &[u8] = &samples[..16];
+    let right: &[u8] = &samples[16..];
+    let planes = [left, right];
+    let frame = AudioFrame {
+        planes: &planes,
+        frames: 4,
+        speakers: SpeakerLayout::Stereo,
+        format: AudioFormat::FloatPlanar,
+        samples_per_sec: 48000,
+        timestamp: obs::get_time_ns(),
+    };
+    source.output_audio(&frame);
+    assert_eq!(fake::output_audio_frames(&source), 4);
+
+    // one plane short, so it's dropped
+    let frame = AudioFrame {
+        planes: &planes[..1],
+        ..frame
+    };
+    source.output_audio(&frame);
+    assert_eq!(fake::output_audio_frames(&source), 4);
+}
+
+#[test]
+fn groups_need_obs_22() {
+    let _globals = lock_globals();
+    let group = || {
+        let mut group = Properties::new();
+        group.add_bool("inner", "Inner");
+        group
+    };
+    let compiled = obs::compiled_version();
+
+    fake::set_version(21, 1, 2);
+    let mut properties = Properties::new();
+    assert!(
+        properties
+            .add_group("group", "Group", GroupType::Normal, group())
+            .is_none()
+    );
+    fake::set_version(compiled.major, compiled.minor, compiled.patch);
+    assert!(fake::property_names(&properties).is_empty());
+
+    assert!(
+        properties
+            .add_group("group", "Group", GroupType::Normal, group())
+            .is_some()
+    );
+    assert_eq!(fake::property_names(&properties), vec!["group", "inner"]);
+}
+
+#[derive(Clone, Default)]
+struct SignalCounts {
+    pings: Arc<AtomicUsize>,
+    destroyed: Arc<AtomicBool>,
+}
+
+struct SignalSourceDefinition(SignalCounts);
+
+impl VideoSourceDefinition for SignalSourceDefinition {
+    type Source = SignalSource;
+    fn create(&self, _settings: &Data, source: &mut ObsSource) -> SignalSource {
+        let handler = source.get_signal_handler();
+        assert!(handler.add_signal("void ping()"));
+        let pings = self.0.pings.clone();
+        SignalSource {
+            _connection: handler.connect("ping", move |_| {
+                pings.fetch_add(1, Ordering::SeqCst);
+            }),
+            destroyed: self.0.destroyed.clone(),
+        }
+    }
+}
+
+// keeps a connection to its own signal handler
+struct SignalSource {
+    _connection: SignalConnection,
+    destroyed: Arc<AtomicBool>,
+}
+
+impl Drop for SignalSource {
+    fn drop(&mut self) {
+        self.destroyed.store(true, Ordering::SeqCst);
+    }
+}
+
+impl VideoSource for SignalSource {
+    fn get_width(&self) -> u32 {
+        0
+    }
+    fn get_height(&self) -> u32 {
+        0
+    }
+}
+
+#[test]
+fn sources_connected_to_themselves_are_destroyed() {
+    let _globals = lock_globals();
+    let counts = SignalCounts::default();
+    obs::register_source("obs-test-signal", "Signal", SignalSourceDefinition(counts.clone()));
+    let source = obs::source_create_private("obs-test-signal", None, None).unwrap();
+    let handler = source.get_signal_handler();
+    handler.signal("ping", &mut CallData::new());
+    assert_eq!(counts.pings.load(Ordering::SeqCst), 1);
+
+    drop(source);
+    assert!(counts.destroyed.load(Ordering::SeqCst));
+    // the handler went with the source
+    handler.signal("ping", &mut CallData::new());
+    assert!(!handler.add_signal("void pong()"));
+    assert_eq!(counts.pings.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn module_loads_only_in_supported_obs() {
+    let _globals = lock_globals();
+    let compiled = obs::compiled_version();
+    unsafe {
+        fake::set_version(20, 2, 3);
+        assert!(!obs_module_load());
+        fake::set_version(compiled.major, compiled.minor, compiled.patch);
+        assert!(obs_module_load());
+        obs_module_unload();
+    }
+    assert_eq!(obs::runtime_version(), compiled);
+}